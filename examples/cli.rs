@@ -47,7 +47,10 @@ ARGS:  <LATITUDE_FLOAT,LONGITUDE_FLOAT>
 
 OPTIONS:
        --elev_data_dir: <ELEVATION_DATA_DIR> or $ELEV_DATA_DIR set
-       {{ --min | --max }} true: get <boundary> of file",
+       {{ --min | --max }} true: get <boundary> of file
+       --resolution: get the resolution of file
+       --interpolate: interpolate between the samples around the coordinate, instead of taking the nearest
+       --validate: check that the file is well-formed, reading all of it",
         if cx.is_empty() { "unknown" } else { cx }
     );
     std::process::exit(1);
@@ -83,17 +86,36 @@ fn main() -> io::Result<()> {
     let file_path = elev_data_dir.join(file_name);
     // eprintln!("path to .hgt file: {}", file_path.display());
 
+    if args.contains(&"--resolution".to_string()) {
+        let res = srtm_reader::Resolution::of_file(&file_path).unwrap();
+        println!("resolution of this file is {res:?}");
+        return Ok(());
+    };
+
+    if args.contains(&"--validate".to_string()) {
+        match srtm_reader::validate_file(&file_path, true) {
+            Ok(res) => println!("{} is a valid {res:?} file", file_path.display()),
+            Err(e) => println!("{} is invalid: {e}", file_path.display()),
+        }
+        return Ok(());
+    };
+
     let data: srtm_reader::Tile = srtm_reader::Tile::from_file(file_path).unwrap();
     // eprintln!("resolution: {:?}", data.resolution);
     if args.contains(&"--max".to_string()) {
-        println!("max elevation in this file is {}", data.max_height());
+        println!("max elevation in this file is {:?}", data.max_height());
         return Ok(());
     };
     if args.contains(&"--min".to_string()) {
-        println!("min elevation in this file is {}", data.min_height());
+        println!("min elevation in this file is {:?}", data.min_height());
+        return Ok(());
+    };
+    if args.contains(&"--interpolate".to_string()) {
+        let elevation = data.get_interpolated(coord.0);
+        println!("Interpolated elevation at {coord} is {elevation:?} meters");
         return Ok(());
     };
-    let elevation = data.get(coord.0);
+    let elevation = data.elevation(coord.0);
 
     // eprintln!("offset: row: {row}, col: {col}");
     // let elevation = coord.get_elevation(&data);
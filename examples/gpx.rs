@@ -16,7 +16,7 @@ fn needed_coords(wps: &[Waypoint]) -> BTreeSet<(i8, i16)> {
     // kinda Waypoint to (i32, i32)
     let trunc = |wp: &Waypoint| -> (i8, i16) {
         let (x, y) = wp.point().x_y();
-        (y.trunc() as i8, x.trunc() as i16)
+        srtm_reader::Coord::lon_lat(x, y).tile_key()
     };
     // tiles we need
     wps.par_iter().filter(|wp| !is_00(wp)).map(trunc).collect()
@@ -39,7 +39,7 @@ fn read_tiles(
         .collect::<Vec<_>>()
 }
 
-fn index_tiles<'a>(tiles: &'a [srtm_reader::Tile]) -> HashMap<(i8, i16), &'a srtm_reader::Tile> {
+fn index_tiles(tiles: &[srtm_reader::Tile]) -> HashMap<(i8, i16), &srtm_reader::Tile> {
     tiles
         .par_iter()
         .map(|tile| ((tile.latitude, tile.longitude), tile))
@@ -52,20 +52,19 @@ fn add_elev(
     overwrite: bool,
 ) -> bool {
     let has_changed = Arc::new(Mutex::new(false));
-    // coord is x,y but we need y,x
-    let xy_yx = |wp: &Waypoint| -> srtm_reader::Coord {
+    let to_coord = |wp: &Waypoint| -> srtm_reader::Coord {
         let (x, y) = wp.point().x_y();
-        (y, x).into()
+        srtm_reader::Coord::lon_lat(x, y)
     };
     wps.into_par_iter()
         .filter(|wp| (wp.elevation.is_none() || overwrite) && !is_00(wp))
         .for_each(|wp| {
-            let coord = xy_yx(wp);
-            if let Some(elev_data) = elev_data.get(&coord.trunc()) {
-                let elev = elev_data.get(coord);
+            let coord = to_coord(wp);
+            if let Some(elev_data) = elev_data.get(&coord.tile_key()) {
+                let elev = elev_data.elevation(coord);
                 let mut x = has_changed.lock().unwrap();
                 *x = true;
-                wp.elevation = elev.map(|x| *x as f64);
+                wp.elevation = elev.map(f64::from);
             }
         });
     let x = has_changed.lock().unwrap();
@@ -0,0 +1,35 @@
+//! time parsing `.hgt` content, eg. `cargo run --release --example parse_bench -- N44E015.hgt`
+//! without a file, a synthetic SRTM05 tile is parsed, the largest resolution
+//! run it with `--features simd` too, to compare the AVX2 byte swap with the scalar one
+use srtm_reader::{Resolution, Tile};
+use std::time::Instant;
+
+const RUNS: usize = 10;
+
+fn main() {
+    let (bytes, res) = match std::env::args().nth(1) {
+        Some(path) => {
+            let bytes = std::fs::read(&path).expect("couldn't read file");
+            let res = Resolution::try_from(bytes.len() as u64).expect("not an .hgt file");
+            (bytes, res)
+        }
+        None => {
+            let res = Resolution::SRTM05;
+            let bytes = (0..res.total_len() * 2).map(|i| (i % 251) as u8).collect();
+            (bytes, res)
+        }
+    };
+
+    let mut best = f64::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let elevations = Tile::parse_hgt(bytes.as_slice(), res).expect("parsing failed");
+        best = best.min(start.elapsed().as_secs_f64());
+        assert_eq!(elevations.len(), res.total_len());
+    }
+    println!(
+        "{res:?}: {:.1}ms, {:.2} GB/s, best of {RUNS}",
+        best * 1e3,
+        bytes.len() as f64 / best / 1e9
+    );
+}
@@ -0,0 +1,120 @@
+//! a tiny HTTP service answering `GET /elevation?lat=44.448&lon=15.073` with JSON,
+//! tiles are loaded on demand and shared between connections
+//!
+//! `cargo run --example server --features tokio -- <ELEVATION_DATA_DIR> [ADDRESS]`
+//! the data directory defaults to `$ELEV_DATA_DIR`, the address to `127.0.0.1:8080`
+use srtm_reader::{Coord, Error, SharedTileSet};
+use std::sync::Arc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+#[tokio::main]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+    let dir = args
+        .next()
+        .or_else(|| std::env::var("ELEV_DATA_DIR").ok())
+        .expect("no elevation data directory given, nor $ELEV_DATA_DIR set");
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8080".into());
+
+    let tiles = Arc::new(SharedTileSet::new(dir));
+    let listener = TcpListener::bind(&addr).await.expect("couldn't bind");
+    eprintln!("listening on http://{addr}/elevation?lat=..&lon=..");
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let tiles = Arc::clone(&tiles);
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, tiles).await {
+                eprintln!("connection failed: {e}");
+            }
+        });
+    }
+}
+
+/// answer a single request on `stream`
+async fn handle(mut stream: TcpStream, tiles: Arc<SharedTileSet>) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&mut stream)
+        .read_line(&mut request_line)
+        .await?;
+    let target = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["GET", target, _] => target.to_owned(),
+        _ => {
+            return respond(
+                &mut stream,
+                "405 Method Not Allowed",
+                r#"{"error":"only GET is supported"}"#.into(),
+            )
+            .await
+        }
+    };
+    let (status, body) = match target.split_once('?') {
+        Some(("/elevation", query)) => match parse_coord(query) {
+            Some(coord) => {
+                // reading a tile blocks, keep it off the async workers
+                let lookup = tokio::task::spawn_blocking(move || tiles.elevation(coord)).await;
+                match lookup {
+                    Ok(Ok(elevation)) => {
+                        let elevation = elevation.map_or("null".into(), |elev| elev.to_string());
+                        let body = format!(
+                            r#"{{"lat":{},"lon":{},"elevation":{elevation}}}"#,
+                            coord.lat, coord.lon
+                        );
+                        ("200 OK", body)
+                    }
+                    Ok(Err(Error::NotFound { .. })) => (
+                        "404 Not Found",
+                        r#"{"error":"no tile for this coordinate"}"#.into(),
+                    ),
+                    Ok(Err(e)) => {
+                        // the details, eg. paths, are for the log, not for clients
+                        eprintln!("couldn't load the tile of {coord:?}: {e}");
+                        (
+                            "500 Internal Server Error",
+                            r#"{"error":"tile unavailable"}"#.into(),
+                        )
+                    }
+                    Err(_) => (
+                        "500 Internal Server Error",
+                        r#"{"error":"lookup failed"}"#.into(),
+                    ),
+                }
+            }
+            None => (
+                "400 Bad Request",
+                r#"{"error":"expected lat and lon in range"}"#.into(),
+            ),
+        },
+        _ => (
+            "404 Not Found",
+            r#"{"error":"try /elevation?lat=..&lon=.."}"#.into(),
+        ),
+    };
+    respond(&mut stream, status, body).await
+}
+
+/// `lat` and `lon` of a query string, eg. `lat=44.448&lon=15.073`
+fn parse_coord(query: &str) -> Option<Coord> {
+    let (mut lat, mut lon) = (None, None);
+    for pair in query.split('&') {
+        match pair.split_once('=') {
+            Some(("lat", value)) => lat = value.parse::<f64>().ok(),
+            Some(("lon", value)) => lon = value.parse::<f64>().ok(),
+            _ => {}
+        }
+    }
+    Coord::opt_new(lat?, lon?)
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: String) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
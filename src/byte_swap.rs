@@ -0,0 +1,57 @@
+//! decoding the big-endian samples of `.hgt` content into native `i16`s
+//!
+//! with the `simd` feature, 16 samples at a time are byte-swapped with AVX2 on `x86_64` CPUs supporting it,
+//! everywhere else, and without the feature, the scalar loop is used, both give the same elevations
+
+/// append the big-endian `i16`s of `bytes` to `out`, a trailing odd byte is ignored
+pub(crate) fn extend_from_be(out: &mut Vec<i16>, bytes: &[u8]) {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if std::is_x86_feature_detected!("avx2") {
+        // SAFETY: the CPU supports AVX2, checked right above
+        return unsafe { extend_from_be_avx2(out, bytes) };
+    }
+    extend_from_be_scalar(out, bytes)
+}
+
+/// [`extend_from_be()`], a sample at a time
+pub(crate) fn extend_from_be_scalar(out: &mut Vec<i16>, bytes: &[u8]) {
+    out.extend(
+        bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_be_bytes([pair[0], pair[1]])),
+    );
+}
+
+/// [`extend_from_be()`], swapping the bytes of 16 samples at once, the rest is left to the scalar loop
+///
+/// # Safety
+/// the CPU must support AVX2
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn extend_from_be_avx2(out: &mut Vec<i16>, bytes: &[u8]) {
+    use std::arch::x86_64::{
+        __m256i, _mm256_loadu_si256, _mm256_setr_epi8, _mm256_shuffle_epi8, _mm256_storeu_si256,
+    };
+
+    const LANES: usize = 16;
+    let chunks = bytes.chunks_exact(LANES * 2);
+    let rest = chunks.remainder();
+    let samples = chunks.len() * LANES;
+    out.reserve(samples + rest.len() / 2);
+    // swaps the two bytes of every sample, the shuffle works within each 16 byte half
+    let swap = _mm256_setr_epi8(
+        1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14, 1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10,
+        13, 12, 15, 14,
+    );
+    let start = out.len();
+    let dst = out.as_mut_ptr().add(start);
+    for (i, chunk) in chunks.enumerate() {
+        // SAFETY: `chunk` is 32 bytes, and `out` has room for `samples` more, 16 for each chunk
+        let swapped =
+            _mm256_shuffle_epi8(_mm256_loadu_si256(chunk.as_ptr().cast::<__m256i>()), swap);
+        _mm256_storeu_si256(dst.add(i * LANES).cast::<__m256i>(), swapped);
+    }
+    // SAFETY: all `samples` are written above
+    out.set_len(start + samples);
+    extend_from_be_scalar(out, rest);
+}
@@ -1,5 +1,17 @@
+use std::path::PathBuf;
+
+/// mean radius of the Earth, in meters
+pub(crate) const EARTH_RADIUS: f64 = 6_371_008.8;
+/// fixed-point degrees of [`Coord::from_microdegrees()`] to degrees
+const FIXED_POINT_SCALE: f64 = 1e7;
+/// the radius of the sphere of Web Mercator, EPSG:3857, the semi-major axis of WGS84, in meters
+const WEB_MERCATOR_RADIUS: f64 = 6_378_137.;
+/// the northernmost and southernmost latitude of Web Mercator, where the map becomes square
+pub const WEB_MERCATOR_MAX_LAT: f64 = 85.051_128_779_806_59;
+
 /// coordinates
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coord {
     /// latitude: north-south
     pub lat: f64,
@@ -20,6 +32,91 @@ impl Coord {
     pub fn new(lat: impl Into<f64>, lon: impl Into<f64>) -> Self {
         Self::opt_new(lat, lon).expect("latitude must be between -90 and 90 degrees, longitude must be between -180 and 180 degrees")
     }
+    /// same as [`Coord::new()`], stating the order of arguments explicitly
+    pub fn lat_lon(lat: impl Into<f64>, lon: impl Into<f64>) -> Self {
+        Self::new(lat, lon)
+    }
+    /// create a [`Coord`] from longitude first, as in `x, y` ordered formats, eg. GeoJSON or gpx
+    pub fn lon_lat(lon: impl Into<f64>, lat: impl Into<f64>) -> Self {
+        Self::new(lat, lon)
+    }
+    /// create a [`Coord`] from fixed-point degrees scaled by 1e7, as in many binary GPS logs and protobuf geo types
+    ///
+    /// # Panics
+    /// same as [`Coord::new()`]
+    pub fn from_microdegrees(lat_e7: i32, lon_e7: i32) -> Self {
+        Self::new(
+            lat_e7 as f64 / FIXED_POINT_SCALE,
+            lon_e7 as f64 / FIXED_POINT_SCALE,
+        )
+    }
+    /// fixed-point degrees scaled by 1e7 as `(lat, lon)`, the inverse of [`Coord::from_microdegrees()`]
+    /// rounded to the nearest unit, about 1cm
+    pub fn to_microdegrees(&self) -> (i32, i32) {
+        (
+            (self.lat * FIXED_POINT_SCALE).round() as i32,
+            (self.lon * FIXED_POINT_SCALE).round() as i32,
+        )
+    }
+    /// create a [`Coord`] from degrees, minutes, seconds and hemisphere: `'N'`, `'S'`, `'E'` or `'W'`
+    /// `None` if a hemisphere doesn't fit its axis, degrees are negative as the hemisphere gives the sign,
+    /// or minutes/seconds aren't below 60, or it's out of range
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use srtm_reader::Coord;
+    /// let coord = Coord::from_dms((44, 26, 53., 'N'), (15, 4, 23.9, 'E')).unwrap();
+    /// assert!((coord.lat - 44.448055).abs() < 1e-6);
+    /// assert!((coord.lon - 15.073305).abs() < 1e-6);
+    /// ```
+    pub fn from_dms(
+        lat_dms: (i32, u32, f64, char),
+        lon_dms: (i32, u32, f64, char),
+    ) -> Option<Self> {
+        let to_decimal =
+            |(deg, min, sec, hemisphere): (i32, u32, f64, char), positive, negative| {
+                if deg < 0 || min >= 60 || !(0. ..60.).contains(&sec) {
+                    return None;
+                }
+                let abs = deg as f64 + min as f64 / 60. + sec / 3600.;
+                match hemisphere.to_ascii_uppercase() {
+                    h if h == positive => Some(abs),
+                    h if h == negative => Some(-abs),
+                    _ => None,
+                }
+            };
+        let lat = to_decimal(lat_dms, 'N', 'S')?;
+        let lon = to_decimal(lon_dms, 'E', 'W')?;
+        Self::opt_new(lat, lon)
+    }
+    /// parse a [`Coord`] like `44°26'53"N 15°4'23.9"E`, see [`Coord::from_dms()`]
+    /// latitude comes first, the two parts may be separated by whitespace and/or a comma
+    pub fn parse_dms(s: &str) -> Option<Self> {
+        let parse_part = |part: &str| -> Option<(i32, u32, f64, char)> {
+            let part = part.trim();
+            let hemisphere = part.chars().last()?;
+            let numbers = part[..part.len() - hemisphere.len_utf8()]
+                .split(['°', '\'', '"', ' '])
+                .filter(|n| !n.is_empty())
+                .collect::<Vec<_>>();
+            match numbers.as_slice() {
+                [deg, min, sec] => Some((
+                    deg.parse().ok()?,
+                    min.parse().ok()?,
+                    sec.parse().ok()?,
+                    hemisphere,
+                )),
+                [deg, min] => Some((deg.parse().ok()?, min.parse().ok()?, 0., hemisphere)),
+                [deg] => Some((deg.parse().ok()?, 0, 0., hemisphere)),
+                _ => None,
+            }
+        };
+        let split = s.find(['N', 'S', 'n', 's'])? + 1;
+        let (lat, lon) = s.split_at(split);
+        let lon = lon.trim_start_matches([',', ' ']);
+        Self::from_dms(parse_part(lat)?, parse_part(lon)?)
+    }
     pub fn with_lat(self, lat: impl Into<f64>) -> Self {
         Self::new(lat, self.lon)
     }
@@ -32,6 +129,103 @@ impl Coord {
     pub fn add_to_lon(self, lon: impl Into<f64>) -> Self {
         self.with_lon(self.lon + lon.into())
     }
+    /// same as [`Coord::add_to_lat()`], but clamps the result to -90..=90 instead of panicking
+    pub fn saturating_add_to_lat(self, lat: impl Into<f64>) -> Self {
+        let lat = (self.lat + lat.into()).clamp(-90., 90.);
+        Self { lat, ..self }
+    }
+    /// same as [`Coord::add_to_lon()`], but wraps the result around the antimeridian instead of panicking
+    pub fn saturating_add_to_lon(self, lon: impl Into<f64>) -> Self {
+        let lon = Self::wrap_longitude(self.lon + lon.into());
+        Self { lon, ..self }
+    }
+    /// the canonical [`Coord`] for any `lat`, `lon`:
+    /// - longitude is wrapped into `-180..180`, as [`Coord::wrap_longitude()`], eg. -190 becomes 170
+    /// - latitude is clamped into `-90..=90`, eg. 91 becomes 90
+    /// - `None` if either is `NaN` or infinite
+    pub fn normalize(lat: f64, lon: f64) -> Option<Coord> {
+        (lat.is_finite() && lon.is_finite()).then(|| Coord {
+            lat: lat.clamp(-90., 90.),
+            lon: Self::wrap_longitude(lon),
+        })
+    }
+    /// create a [`Coord`] from Web Mercator, EPSG:3857, `x` easting and `y` northing in meters
+    /// `x` beyond the antimeridian wraps around, `y` beyond [`WEB_MERCATOR_MAX_LAT`] is still converted,
+    /// the inverse of [`Coord::to_web_mercator()`]
+    pub fn from_web_mercator(x: f64, y: f64) -> Coord {
+        let lon = (x / WEB_MERCATOR_RADIUS).to_degrees();
+        Coord {
+            lat: (y / WEB_MERCATOR_RADIUS).sinh().atan().to_degrees(),
+            lon: if (-180. ..=180.).contains(&lon) {
+                lon
+            } else {
+                Self::wrap_longitude(lon)
+            },
+        }
+    }
+    /// Web Mercator, EPSG:3857, easting and northing of `self` in meters, as `(x, y)`
+    /// latitude is clamped to [`WEB_MERCATOR_MAX_LAT`], as the poles are infinitely far
+    pub fn to_web_mercator(&self) -> (f64, f64) {
+        let lat = self
+            .lat
+            .clamp(-WEB_MERCATOR_MAX_LAT, WEB_MERCATOR_MAX_LAT)
+            .to_radians();
+        let y = (std::f64::consts::FRAC_PI_4 + lat / 2.).tan().ln();
+        (
+            self.lon.to_radians() * WEB_MERCATOR_RADIUS,
+            y * WEB_MERCATOR_RADIUS,
+        )
+    }
+    /// wrap `lon` into -180..180, eg. 190 becomes -170
+    pub fn wrap_longitude(lon: f64) -> f64 {
+        (lon + 180.).rem_euclid(360.) - 180.
+    }
+
+    /// whether latitude and longitude were likely passed in the wrong order:
+    /// `self` is out of range, but would be valid with them swapped, eg. `(150, 44)`
+    pub fn looks_swapped(&self) -> bool {
+        Self::opt_new(self.lat, self.lon).is_none() && Self::opt_new(self.lon, self.lat).is_some()
+    }
+    /// swap latitude and longitude, if [`Coord::looks_swapped()`]
+    pub fn try_fix_swap(self) -> Self {
+        if self.looks_swapped() {
+            Self {
+                lat: self.lon,
+                lon: self.lat,
+            }
+        } else {
+            self
+        }
+    }
+
+    /// the great-circle distance between `self` and `other`, in meters, using the haversine formula
+    pub fn distance_to(&self, other: impl Into<Coord>) -> f64 {
+        let other = other.into();
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.lon - self.lon).to_radians();
+        let a = (d_lat / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.).sin().powi(2);
+        2. * EARTH_RADIUS * a.sqrt().asin()
+    }
+    /// the initial great-circle bearing from `self` towards `other`, in degrees clockwise from north, in `0..360`
+    pub fn bearing_to(&self, other: impl Into<Coord>) -> f64 {
+        let other = other.into();
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lon = (other.lon - self.lon).to_radians();
+        let y = d_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.)
+    }
+
+    /// round both latitude and longitude to `decimals` decimal places, halfway cases away from zero
+    /// eg. 5 decimals is about 1m, for stable cache keys from noisy GPS points
+    pub fn round_to(&self, decimals: u32) -> Coord {
+        let factor = 10f64.powi(decimals as i32);
+        Coord {
+            lat: (self.lat * factor).round() / factor,
+            lon: (self.lon * factor).round() / factor,
+        }
+    }
 
     /// truncate both latitude and longitude
     /// use no_std compatible `to_int_unchecked` method
@@ -41,7 +235,45 @@ impl Coord {
         (lat_trunc, lon_trunc)
     }
 
-    /// get the name of the file, which shall include this `coord`s elevation
+    /// the latitude and longitude of the south-west corner of the [`crate::Tile`] this `coord` falls into
+    ///
+    /// a tile covers from its corner up to, but excluding the next whole degree, so both are floored:
+    /// - `(0.9999, 0.9999)` is in `N00E000`, `(1.0, 1.0)` is in `N01E001`, whose south-west corner sample it is
+    /// - `(-0.5, -0.5)` is in `S01W001`, while `(0.0, 0.0)` and `(-0.0, -0.0)` are both in `N00E000`
+    /// - as there are no tiles beyond them, `90°` latitude is in the `N89` row, `180°` longitude in the `E179` column
+    /// - anything further out is clamped to the outermost row or column, NaN to `0`
+    ///
+    /// samples on a boundary are stored in both tiles sharing it, so either tile returns the same elevation
+    pub fn tile_key(&self) -> (i8, i16) {
+        // the cast saturates, and maps NaN to 0
+        let lat = (self.lat.floor() as i16).clamp(-90, 89) as i8;
+        let lon = (self.lon.floor() as i16).clamp(-180, 179);
+        (lat, lon)
+    }
+
+    /// the path of the file which shall include this `coord`s elevation, relative to the root of a mirror of `layout`
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// use srtm_reader::{coords::TileLayout, Coord};
+    /// use std::path::PathBuf;
+    ///
+    /// let coord = Coord::new(44.4480403, 15.0733053);
+    /// assert_eq!(coord.tile_path(TileLayout::Flat), PathBuf::from("N44E015.hgt"));
+    /// assert_eq!(coord.tile_path(TileLayout::ContinentSubdir), PathBuf::from("Eurasia/N44E015.hgt"));
+    /// ```
+    pub fn tile_path(&self, layout: TileLayout) -> PathBuf {
+        match layout {
+            TileLayout::Flat => PathBuf::from(self.get_filename()),
+            TileLayout::ContinentSubdir => {
+                PathBuf::from(self.continent()).join(self.get_filename())
+            }
+            TileLayout::Custom(path) => path(*self),
+        }
+    }
+
+    /// get the name of the file, which shall include this `coord`s elevation, see [`Coord::tile_key()`]
     ///
     /// # Usage
     ///
@@ -52,9 +284,9 @@ impl Coord {
     /// assert_eq!(filename, "N87E010.hgt");
     /// ```
     pub fn get_filename(self) -> String {
-        let lat_ch = if self.lat >= 0. { 'N' } else { 'S' };
-        let lon_ch = if self.lon >= 0. { 'E' } else { 'W' };
-        let (lat, lon) = self.trunc();
+        let (lat, lon) = self.tile_key();
+        let lat_ch = if lat >= 0 { 'N' } else { 'S' };
+        let lon_ch = if lon >= 0 { 'E' } else { 'W' };
         let (lat, lon) = (lat.abs(), lon.abs());
         format!(
             "{lat_ch}{}{lat}{lon_ch}{}{lon}.hgt",
@@ -70,9 +302,48 @@ impl Coord {
     }
 }
 
+// impl for non-pub fn-s
+impl Coord {
+    /// the continent directory of the SRTM3 v2.1 mirror this `coord` is sorted into
+    /// approximated with bounding boxes, so tiles near the borders, and those in `Islands` may be off
+    fn continent(&self) -> &'static str {
+        let (lat, lon) = (self.lat, self.lon);
+        if lat >= 15. && lon < -50. || (7. ..15.).contains(&lat) && (-93. ..-77.).contains(&lon) {
+            "North_America"
+        } else if lat < 15. && (-93. ..-32.).contains(&lon) {
+            "South_America"
+        } else if lat < 38. && (-20. ..52.).contains(&lon) && !(lat >= 12. && lon >= 35.) {
+            "Africa"
+        } else if lat < -10. && lon >= 110. {
+            "Australia"
+        } else {
+            "Eurasia"
+        }
+    }
+}
+
+/// how a mirror of SRTM data organizes its files, see [`Coord::tile_path()`]
+#[derive(Clone, Copy)]
+pub enum TileLayout<'a> {
+    /// all files in one directory, as [`Coord::get_filename()`]
+    Flat,
+    /// files in directories by continent, eg. `Eurasia/N44E015.hgt`, as the SRTM3 v2.1 mirror of USGS
+    ContinentSubdir,
+    /// any other convention, the relative path of the file of a `Coord`
+    Custom(&'a dyn Fn(Coord) -> PathBuf),
+}
+
 impl<F1: Into<f64>, F2: Into<f64>> From<(F1, F2)> for Coord {
     fn from(value: (F1, F2)) -> Self {
         let (lat, lon) = (value.0.into(), value.1.into());
         Coord { lat, lon }
     }
 }
+
+/// `x` is longitude, `y` is latitude
+#[cfg(feature = "geo-types")]
+impl From<geo_types::Point<f64>> for Coord {
+    fn from(point: geo_types::Point<f64>) -> Self {
+        Coord::lon_lat(point.x(), point.y())
+    }
+}
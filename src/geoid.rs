@@ -0,0 +1,77 @@
+//! a coarse EGM96 geoid, to relate SRTM heights to GPS ones
+//!
+//! SRTM heights are orthometric: above the EGM96 geoid, GPS receivers report heights above the WGS84 ellipsoid.
+//! the difference is the geoid undulation, up to about 100m
+
+use super::Coord;
+
+/// spacing of [`UNDULATIONS`], in degrees
+const GRID_STEP: f64 = 5.;
+/// EGM96 geoid undulations in decimeters, on a 5° grid
+/// rows from 90° north to 90° south, columns from 180° west eastwards, 175° east wraps to the first column
+#[rustfmt::skip]
+const UNDULATIONS: [[i16; 72]; 37] = [
+    [136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136, 136],
+    [78, 86, 90, 95, 97, 99, 100, 100, 107, 113, 114, 119, 126, 134, 138, 144, 152, 159, 162, 166, 176, 187, 200, 209, 217, 217, 208, 210, 220, 235, 244, 251, 262, 259, 248, 245, 251, 250, 248, 247, 242, 239, 229, 213, 203, 193, 183, 173, 162, 151, 144, 138, 132, 130, 121, 120, 121, 113, 109, 100, 89, 88, 79, 72, 91, 90, 71, 67, 60, 59, 69, 73],
+    [44, 35, 27, 19, 7, 0, -1, -7, -15, -18, -30, -25, -6, 32, 41, 18, 32, 75, 109, 79, 146, 128, 72, 113, 185, 241, 280, 311, 339, 365, 364, 327, 319, 313, 323, 344, 339, 352, 354, 334, 298, 273, 243, 218, 183, 160, 134, 111, 94, 69, 43, 38, 24, 8, 15, 21, 25, 21, -4, -10, -5, 1, -9, -7, 3, 22, 32, 17, 6, 5, 20, 22],
+    [18, 23, 27, 1, -18, -47, -41, -40, -74, -91, -41, -96, -117, -114, -131, -138, -108, -74, -40, 22, 61, 139, 182, 182, 189, 248, 317, 350, 375, 411, 447, 458, 442, 461, 459, 454, 437, 436, 412, 404, 311, 256, 206, 166, 117, 80, 45, 15, 7, -33, -45, -61, -70, -72, -73, -86, -93, -91, -62, -52, -35, -24, -18, -13, 1, 7, 9, 12, 15, 20, 26, 19],
+    [26, 5, 8, 3, -7, -3, -14, -17, -29, -43, -80, -115, -155, -180, -248, -283, -275, -259, -257, -223, -184, -78, 28, 104, 230, 279, 299, 393, 456, 535, 572, 578, 596, 593, 557, 513, 488, 455, 418, 381, 295, 245, 199, 150, 107, 73, 30, -11, -30, -71, -93, -103, -126, -143, -133, -138, -150, -126, -92, -114, -88, -97, -61, -32, -7, -1, 22, 23, 32, 43, 42, 36],
+    [47, 47, 44, 67, 86, 95, 108, 121, 110, 77, 19, -106, -162, -215, -279, -344, -403, -402, -373, -346, -319, -249, -135, -24, 162, 258, 328, 479, 444, 560, 586, 643, 669, 659, 596, 533, 508, 439, 401, 321, 252, 177, 180, 174, 125, 82, 64, -1, -11, -98, -141, -184, -197, -209, -228, -248, -239, -220, -191, -180, -170, -164, -41, 5, 41, 88, 72, 84, 95, 108, 86, 60],
+    [4, 62, 92, 119, 152, 148, 135, 116, 105, 67, 5, -68, -159, -242, -310, -359, -422, -479, -470, -455, -424, -299, -231, -84, 48, 165, 285, 412, 488, 558, 638, 633, 607, 599, 555, 542, 482, 450, 405, 293, 186, 177, 160, 155, 114, 100, 60, 3, -39, -146, -213, -264, -306, -317, -339, -331, -342, -311, -277, -257, -166, -58, 6, 53, 122, 137, 162, 160, 144, 125, 89, -19],
+    [-7, 49, 92, 155, 171, 99, 110, 56, 4, -66, -71, -102, -154, -190, -251, -288, -328, -398, -446, -464, -444, -366, -262, -162, -83, 125, 251, 388, 496, 597, 602, 613, 608, 576, 579, 551, 462, 409, 400, 337, 265, 242, 194, 157, 111, 64, 16, -62, -95, -199, -263, -334, -363, -370, -376, -379, -402, -345, -298, -185, -94, 3, 92, 145, 157, 160, 193, 209, 224, 6, 49, -22],
+    [-57, -5, 55, 86, 74, 45, 0, -53, -115, -166, -180, -168, -160, -141, -174, -193, -238, -300, -350, -398, -395, -357, -263, -227, -128, 81, 242, 324, 445, 541, 624, 634, 617, 566, 584, 529, 450, 467, 480, 448, 400, 319, 273, 160, 105, 4, -118, -169, -194, -292, -334, -378, -445, -425, -421, -459, -417, -398, -295, -188, -45, 87, 150, 206, 225, 192, 203, 238, 63, 55, 26, 9],
+    [-64, -47, -33, -28, -42, -83, -133, -178, -236, -274, -262, -269, -192, -140, -75, -146, -225, -273, -338, -350, -366, -321, -262, -220, -154, -17, 170, 279, 392, 506, 613, 650, 608, 572, 510, 449, 471, 507, 390, 455, 440, 365, 310, 217, 129, 6, -110, -202, -289, -365, -424, -473, -403, -626, -593, -529, -493, -436, -319, -148, 4, 110, 227, 287, 282, 256, 136, 116, 47, -34, -33, -75],
+    [-125, -123, -109, -114, -131, -156, -208, -263, -315, -350, -366, -337, -233, -194, -184, -172, -251, -321, -333, -345, -339, -340, -346, -339, -270, -151, 12, 161, 323, 459, 588, 576, 519, 484, 485, 545, 511, 455, 457, 464, 342, 403, 385, 339, 295, 203, -159, -153, -283, -364, -411, -355, -557, -655, -666, -589, -553, -446, -288, -107, 27, 172, 230, 302, 377, 169, 166, 63, -18, -83, -107, -119],
+    [-101, -122, -133, -131, -147, -169, -217, -268, -325, -377, -403, -407, -340, -304, -245, -219, -282, -324, -283, -310, -322, -428, -452, -386, -313, -231, -75, 93, 267, 408, 444, 425, 410, 464, 430, 436, 496, 374, 368, 339, 230, 200, 68, 194, 197, 59, 65, -91, -168, -221, -324, -284, -248, -349, -404, -423, -398, -395, -315, -156, 44, 198, 299, 364, 337, 234, 148, 53, -16, -90, -119, -99],
+    [-75, -75, -53, -60, -87, -125, -158, -215, -281, -343, -405, -434, -424, -346, -298, -240, -231, -285, -273, -286, -324, -458, -517, -448, -408, -326, -176, 13, 163, 248, 302, 301, 342, 395, 434, 463, 354, 265, 277, 298, 258, 212, 162, 173, 98, -42, -177, -65, -151, -270, -388, -465, -345, -278, -341, -385, -317, -423, -237, -108, 70, 232, 304, 360, 423, 300, 193, 108, 33, -37, -68, -69],
+    [-27, 0, 75, 39, 33, -19, -110, -174, -247, -331, -406, -455, -466, -426, -351, -206, -162, -323, -270, -271, -283, -433, -521, -511, -494, -409, -262, -83, 11, 93, 185, 220, 305, 340, 379, 304, 273, 351, 326, 253, 221, 149, 129, 116, 95, -15, -268, -331, -318, -420, -459, -518, -595, -615, -538, -505, -389, -318, -214, -36, 150, 281, 332, 369, 456, 383, 280, 191, 101, 28, -15, -40],
+    [40, 47, 90, 86, 75, 124, -70, -148, -228, -314, -394, -453, -476, -430, -339, -209, -70, -192, -107, -148, -192, -274, -472, -629, -472, -426, -330, -184, -102, 20, 170, 226, 253, 336, 303, 269, 314, 267, 265, 201, 144, 137, 95, 63, 19, -99, -309, -365, -427, -547, -609, -667, -679, -632, -595, -512, -374, -281, -127, 54, 213, 326, 378, 416, 489, 466, 409, 283, 216, 143, 90, 52],
+    [88, 79, 81, 96, 53, 8, -43, -102, -165, -261, -361, -424, -448, -411, -324, -210, -111, -102, 21, 34, -57, -180, -302, -398, -554, -488, -383, -258, -169, -54, 78, 204, 232, 309, 301, 283, 222, 208, 198, 137, 101, 88, 37, 0, -36, -83, -211, -340, -536, -670, -807, -835, -868, -831, -641, -502, -342, -212, -27, 180, 406, 423, 475, 503, 524, 550, 439, 346, 286, 226, 164, 120],
+    [127, 103, 117, 148, 102, 62, 11, -39, -114, -209, -293, -361, -394, -368, -291, -200, -116, -46, 13, 105, 8, -48, -108, -283, -418, -449, -428, -324, -163, -71, 28, 114, 171, 304, 327, 279, 233, 231, 216, 126, 16, -13, -53, -40, -97, -205, -321, -508, -582, -759, -912, -973, -960, -877, -633, -461, -254, -91, 108, 325, 520, 647, 594, 596, 613, 553, 450, 413, 349, 275, 250, 190],
+    [161, 145, 122, 145, 163, 142, 86, 29, -43, -132, -199, -262, -302, -301, -255, -194, -125, -70, -14, 61, 134, 231, -16, -87, -189, -341, -363, -312, -184, -39, 66, 121, 168, 199, 269, 256, 205, 209, 201, 90, -75, -99, -115, -134, -209, -339, -487, -536, -617, -775, -939, -1009, -1060, -901, -604, -418, -126, 31, 229, 441, 618, 637, 699, 683, 655, 630, 549, 517, 447, 343, 277, 222],
+    [212, 176, 152, 151, 163, 157, 127, 76, 7, -69, -132, -195, -231, -234, -204, -176, -142, -107, -43, 9, 144, 196, 137, -23, -126, -249, -257, -232, -186, -84, 22, 101, 126, 188, 195, 189, 172, 163, 90, -12, -173, -190, -98, -163, -270, -434, -489, -517, -627, -753, -895, -1003, -1027, -888, -632, -341, -72, 144, 339, 542, 583, 508, 766, 720, 722, 692, 633, 591, 493, 406, 319, 253],
+    [244, 188, 168, 170, 140, 122, 101, 58, 9, -52, -99, -138, -163, -165, -143, -136, -143, -136, -102, -23, 166, 146, 196, 61, -76, -202, -237, -253, -123, -65, -52, -10, 67, 150, 183, 168, 168, 92, 55, 20, -97, -165, -148, -188, -299, -418, -428, -412, -539, -665, -785, -900, -929, -833, -643, -415, -188, 132, 278, 427, 540, 563, 548, 675, 758, 704, 818, 640, 594, 474, 395, 311],
+    [352, 274, 215, 149, 109, 70, 49, -6, -25, -60, -92, -104, -110, -96, -78, -94, -120, -112, -94, -40, -8, 265, 274, 185, 33, -102, -189, -160, -119, -116, -95, -59, 39, 105, 127, 134, 115, 99, 130, 172, 63, -52, -127, -153, -267, -326, -325, -342, -381, -505, -609, -727, -759, -712, -645, -469, -267, -60, -22, 110, 364, 331, 522, 628, 692, 709, 784, 712, 632, 582, 506, 415],
+    [491, 418, 260, 137, 90, 60, 12, -28, -67, -101, -96, -86, -66, -39, -33, -51, -66, -70, -56, -29, 70, 304, 466, 260, 164, -20, -92, -128, -109, -106, -71, -62, -1, 73, 110, 130, 134, 136, 162, 225, 130, 45, -80, -122, -202, -261, -148, -220, -238, -309, -408, -520, -573, -612, -578, -486, -396, -284, -141, -13, 154, 318, 391, 484, 537, 652, 634, 627, 636, 590, 649, 580],
+    [497, 520, 262, 145, 93, 43, -9, -58, -90, -114, -114, -93, -55, -30, -23, -32, -40, -32, -15, 13, 82, 165, 336, 418, 197, 38, -81, -70, -67, -83, -60, -64, -6, 69, 122, 135, 163, 194, 231, 259, 216, 125, 73, -40, -105, -64, -112, -108, -105, -125, -206, -316, -404, -467, -482, -483, -461, -351, -258, -124, 44, 136, 244, 331, 460, 552, 574, 549, 567, 589, 640, 611],
+    [498, 282, 247, 138, 79, 19, -26, -68, -108, -127, -115, -100, -73, -52, -46, -31, -16, -1, 18, 43, 84, 166, 358, 293, 211, 61, 41, -57, -113, -72, -49, -10, 43, 103, 133, 156, 198, 258, 239, 316, 276, 238, 210, 107, 34, 80, 8, 11, 5, -4, -47, -137, -236, -318, -383, -426, -417, -378, -336, -183, -88, -39, 31, 134, 258, 345, 462, 431, 480, 476, 533, 520],
+    [475, 307, 225, 135, 56, 5, -14, -63, -88, -121, -132, -114, -105, -93, -79, -63, -44, -25, 9, 26, 80, 147, 367, 239, 178, 112, 28, -73, -87, -1, 35, 58, 102, 142, 149, 167, 214, 235, 259, 317, 337, 323, 302, 204, 136, 192, 144, 147, 149, 104, 61, 2, -93, -178, -252, -321, -377, -392, -396, -292, -226, -177, -144, 4, 126, 223, 317, 293, 334, 360, 438, 450],
+    [284, 254, 172, 104, 48, -3, -38, -76, -96, -111, -111, -111, -119, -112, -98, -82, -61, -38, -23, 9, 73, 128, 301, 197, 173, 125, -2, -63, -72, -27, 66, 123, 171, 187, 169, 178, 178, 202, 241, 255, 315, 260, 263, 289, 256, 332, 309, 304, 223, 209, 150, 96, 36, -58, -148, -239, -315, -397, -389, -348, -346, -332, -284, -105, 11, 97, 204, 152, 194, 261, 318, 381],
+    [205, 128, 62, 27, 9, -17, -69, -104, -120, -128, -127, -121, -129, -117, -103, -89, -71, -31, -11, 10, 69, 96, 227, 147, 136, 51, -26, -64, -68, -22, 63, 144, 209, 239, 232, 200, 175, 196, 248, 279, 294, 341, 330, 331, 382, 428, 405, 347, 287, 261, 232, 178, 131, 58, -25, -120, -210, -280, -326, -341, -336, -311, -275, -211, -151, -17, -23, 20, 49, 118, 191, 129],
+    [32, -1, -61, -86, -81, -85, -115, -134, -150, -152, -143, -122, -109, -102, -97, -85, -66, -37, -10, 31, 72, 134, 169, 119, 87, 1, -31, -35, -48, -16, 68, 150, 220, 263, 257, 243, 212, 219, 261, 285, 325, 333, 343, 439, 464, 456, 449, 419, 356, 317, 309, 268, 214, 150, 76, -7, -94, -170, -235, -271, -274, -267, -244, -204, -172, -146, -118, -93, -67, -28, 77, 57],
+    [-166, -195, -187, -184, -185, -180, -161, -165, -174, -178, -158, -124, -102, -100, -106, -104, -86, -67, -34, 4, 49, 135, 115, 113, 124, 78, 25, 32, 34, 57, 111, 158, 204, 246, 269, 266, 250, 248, 251, 297, 332, 351, 376, 407, 450, 449, 445, 428, 382, 404, 399, 330, 272, 202, 127, 57, -22, -93, -152, -201, -227, -236, -224, -199, -185, -171, -171, -169, -140, -123, -101, -118],
+    [-341, -344, -323, -305, -273, -249, -232, -221, -195, -184, -178, -149, -126, -130, -135, -131, -111, -105, -62, -33, 15, 34, 127, 123, 168, 149, 148, 157, 163, 215, 85, 149, 205, 223, 244, 265, 271, 260, 242, 269, 316, 337, 346, 390, 445, 439, 408, 392, 367, 348, 347, 333, 247, 189, 129, 67, 3, -61, -121, -179, -216, -247, -248, -245, -232, -207, -215, -231, -264, -269, -299, -334],
+    [-463, -446, -429, -404, -373, -342, -324, -312, -302, -290, -267, -249, -236, -230, -226, -199, -169, -139, -111, -72, -35, 23, 94, 166, 202, 204, 196, 161, 219, 227, 235, 103, 208, 192, 167, 155, 151, 165, 199, 236, 254, 272, 289, 316, 337, 344, 329, 326, 326, 308, 294, 274, 250, 174, 89, 30, -35, -79, -136, -196, -246, -283, -314, -329, -330, -310, -297, -306, -350, -401, -445, -468],
+    [-515, -514, -507, -497, -487, -472, -459, -433, -419, -405, -382, -346, -322, -303, -276, -251, -228, -196, -146, -110, -60, 3, 62, 148, 181, 189, 130, 140, 142, 129, 115, 103, 94, 86, 88, 105, 138, 170, 175, 197, 188, 194, 211, 236, 264, 282, 301, 305, 277, 269, 239, 203, 162, 131, 71, 34, -10, -93, -172, -201, -289, -329, -355, -394, -443, -452, -456, -437, -444, -469, -501, -517],
+    [-619, -639, -632, -617, -614, -604, -573, -550, -516, -475, -436, -398, -368, -347, -328, -307, -261, -247, -207, -119, -95, -38, 37, 130, 52, 6, 3, 17, 25, 23, 14, 20, 24, 41, 88, 105, 145, 149, 141, 167, 159, 212, 208, 214, 209, 267, 267, 267, 257, 284, 173, 166, 156, 130, 82, 27, -59, -130, -183, -243, -290, -323, -369, -415, -439, -508, -549, -556, -562, -575, -589, -594],
+    [-615, -633, -630, -633, -639, -631, -620, -540, -480, -436, -418, -383, -361, -346, -298, -268, -252, -215, -192, -170, -151, -117, -63, -87, -98, -96, -85, -66, -43, -25, -18, -14, -7, 44, 102, 110, 93, 106, 128, 149, 161, 167, 165, 158, 150, 157, 159, 151, 129, 130, 90, 79, 69, 57, 23, -51, -106, -169, -245, -310, -359, -393, -435, -484, -514, -553, -577, -581, -558, -576, -585, -585],
+    [-526, -526, -526, -527, -524, -525, -514, -496, -480, -463, -416, -406, -390, -383, -372, -325, -284, -247, -224, -221, -246, -235, -221, -208, -207, -218, -201, -172, -179, -174, -171, -151, -132, -116, -103, -85, -71, -51, -30, -14, 8, 28, 39, 23, 37, 51, 56, 82, 75, 54, 47, 58, 37, 11, -23, -84, -137, -190, -240, -297, -338, -386, -409, -445, -492, -499, -501, -506, -544, -518, -508, -515],
+    [-368, -398, -392, -411, -416, -422, -427, -409, -395, -379, -377, -373, -342, -342, -339, -302, -286, -271, -265, -289, -295, -277, -246, -224, -248, -250, -248, -249, -195, -174, -188, -177, -173, -164, -156, -161, -155, -158, -158, -138, -136, -128, -108, -98, -93, -99, -102, -100, -103, -104, -108, -121, -141, -159, -181, -203, -220, -239, -260, -287, -315, -337, -351, -355, -361, -366, -363, -372, -360, -362, -378, -345],
+    [-295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295, -295],
+];
+
+impl Coord {
+    /// the height of the EGM96 geoid above the WGS84 ellipsoid at `self`, in meters
+    ///
+    /// interpolated bilinearly from a 5° grid, so it can be off by a few meters where the geoid is rough
+    pub fn geoid_undulation(&self) -> f64 {
+        let row = ((90. - self.lat) / GRID_STEP).clamp(0., (UNDULATIONS.len() - 1) as f64);
+        let col = (self.lon + 180.).rem_euclid(360.) / GRID_STEP;
+        let (row0, col0) = (row.floor() as usize, col.floor() as usize);
+        let row1 = (row0 + 1).min(UNDULATIONS.len() - 1);
+        let (col0, col1) = (col0 % 72, (col0 + 1) % 72);
+        let (dr, dc) = (row - row.floor(), col - col.floor());
+        let at = |row: usize, col: usize| UNDULATIONS[row][col] as f64 / 10.;
+        let north = at(row0, col0) * (1. - dc) + at(row0, col1) * dc;
+        let south = at(row1, col0) * (1. - dc) + at(row1, col1) * dc;
+        north * (1. - dr) + south * dr
+    }
+    /// convert an SRTM (orthometric) height at `self` to a height above the WGS84 ellipsoid, as reported by GPS
+    pub fn ellipsoidal_height(&self, orthometric_m: f64) -> f64 {
+        orthometric_m + self.geoid_undulation()
+    }
+    /// convert a GPS (ellipsoidal) height at `self` to a height above the geoid, comparable to SRTM
+    pub fn orthometric_height(&self, ellipsoidal_m: f64) -> f64 {
+        ellipsoidal_m - self.geoid_undulation()
+    }
+}
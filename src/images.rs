@@ -0,0 +1,105 @@
+//! rendering the elevations of a [`Tile`] as images
+
+use super::{Error, Tile};
+
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::Path,
+};
+
+/// a palette mapping the valid elevation range of a [`Tile`] to colors
+/// from the lowest elevation at `0` to the highest at `1`, interpolated in RGB between stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorRamp {
+    /// black to white
+    Grayscale,
+    /// hypsometric tints: green lowlands, yellow hills, brown mountains and white peaks
+    #[default]
+    Terrain,
+    /// perceptually uniform, dark purple to yellow
+    Viridis,
+}
+
+impl ColorRamp {
+    /// the stops of this ramp, ascending positions in `0..=1` and their colors
+    fn stops(&self) -> &'static [(f64, [u8; 3])] {
+        match self {
+            ColorRamp::Grayscale => &[(0., [0, 0, 0]), (1., [255, 255, 255])],
+            ColorRamp::Terrain => &[
+                (0., [0, 97, 71]),
+                (0.25, [16, 122, 47]),
+                (0.5, [232, 215, 125]),
+                (0.75, [161, 67, 0]),
+                (1., [255, 255, 255]),
+            ],
+            ColorRamp::Viridis => &[
+                (0., [68, 1, 84]),
+                (0.25, [59, 82, 139]),
+                (0.5, [33, 145, 140]),
+                (0.75, [94, 201, 98]),
+                (1., [253, 231, 37]),
+            ],
+        }
+    }
+    /// the color at `t`, clamped into `0..=1`
+    pub fn color(&self, t: f64) -> [u8; 3] {
+        let t = t.clamp(0., 1.);
+        let stops = self.stops();
+        let upper = stops
+            .iter()
+            .position(|(pos, _)| *pos >= t)
+            .unwrap_or(stops.len() - 1)
+            .max(1);
+        let ((from, low), (to, high)) = (stops[upper - 1], stops[upper]);
+        let ratio = (t - from) / (to - from);
+        std::array::from_fn(|i| {
+            (low[i] as f64 + (high[i] as f64 - low[i] as f64) * ratio).round() as u8
+        })
+    }
+}
+
+impl Tile {
+    /// write this [`Tile`] as an RGBA PNG of `extent` x `extent` pixels to `path`, north up
+    ///
+    /// the valid elevations are stretched over `ramp`, voids are transparent
+    ///
+    /// # Errors
+    /// [`Error::Write`] if the file couldn't be created or encoded
+    pub fn write_png_colored<P: AsRef<Path>>(&self, path: P, ramp: ColorRamp) -> Result<(), Error> {
+        let extent = self.resolution.extent();
+        let (min, max) = self.elevation_range().unwrap_or_default();
+        // in `f64`, as the range of valid elevations may not fit into an `i16`
+        let span = if max > min {
+            max as f64 - min as f64
+        } else {
+            1.
+        };
+        let pixels: Vec<u8> = self
+            .data
+            .iter()
+            .flat_map(|elev| {
+                if self.treats_as_void(*elev) {
+                    return [0; 4];
+                }
+                let [r, g, b] = ramp.color((*elev as f64 - min as f64) / span);
+                [r, g, b, u8::MAX]
+            })
+            .collect();
+
+        let write_error = |e: png::EncodingError| {
+            let source = match e {
+                png::EncodingError::IoError(e) => e,
+                e => io::Error::other(e),
+            };
+            Error::write(source).in_file(&path)
+        };
+        let file = File::create(&path).map_err(|e| Error::write(e).in_file(&path))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), extent as u32, extent as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(write_error)?;
+        writer.write_image_data(&pixels).map_err(write_error)?;
+        writer.finish().map_err(write_error)
+    }
+}
@@ -18,28 +18,228 @@
 //! // load the srtm tile: .hgt file
 //! let tile = Tile::from_file(filename).unwrap();
 //! // and finally, retrieve our elevation for Veli Brig
-//! let elevation = tile.get(coord).unwrap();
+//! let elevation = tile.elevation(coord).unwrap();
 //! // test with a ± 5m accuracy
 //! assert!((TRUE_ELEV - 5..TRUE_ELEV + 5).contains(&elevation));
 //! println!("Veli Brig:\n\t- coordinates: {coord:?}\n\t- elevation\n\t\t- actual: {TRUE_ELEV}m\n\t\t- calculated: {elevation}m");
 //! ```
 
-pub use coords::Coord;
+pub use coords::{Coord, TileLayout};
+#[cfg(feature = "png")]
+pub use images::ColorRamp;
+pub use partial_tiles::PartialTile;
 pub use resolutions::Resolution;
-pub use tiles::Tile;
+pub use terrain::{SmoothKind, HILLSHADE_VOID};
+pub use tile_sets::{GradePoint, SharedTileSet, TileSet};
+pub use tiles::{Lookup, ParseOptions, Tile, TileBuilder, TileId, TileSummary};
 
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+mod byte_swap;
 pub mod coords;
+#[cfg(feature = "geoid")]
+mod geoid;
+#[cfg(feature = "png")]
+pub mod images;
+pub mod partial_tiles;
+mod resampling;
 pub mod resolutions;
+mod terrain;
 #[cfg(test)]
 mod tests;
+pub mod tile_sets;
 pub mod tiles;
+mod void_filling;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
-    NotFound,
+    /// the file at `path` doesn't exist
+    NotFound { path: PathBuf, source: IoError },
+    /// latitude and longitude are out of range, or aren't a valid corner of a tile
     ParseLatLong,
-    Filesize,
-    Read,
+    /// a tile name ends before its latitude and longitude, or goes on after them, eg. `N44.hgt`, `N44E015x.hgt`
+    FilenameLength,
+    /// a tile name doesn't have `N`/`S` and `E`/`W` where the hemispheres belong, eg. `N44X015.hgt`
+    FilenameHemisphere,
+    /// a hemisphere of a tile name isn't followed by 1 to 3 digits, eg. `NX4E015.hgt`, `N44E0150.hgt`
+    FilenameDigits,
+    /// the size doesn't match any known resolution, or the number of samples expected
+    /// `path` is the file, if it's read from one
+    Filesize { path: Option<PathBuf> },
+    /// elevation data couldn't be read, or its file couldn't be opened for another reason than not existing
+    /// `path` is the file, if it's read from one, `source` the cause, if there's one
+    Read {
+        path: Option<PathBuf>,
+        source: Option<IoError>,
+    },
+    /// too many elevations are outside of what's possible on Earth, see [`ParseOptions::validate`]
+    ImplausibleData,
+    /// the output couldn't be written
+    /// `path` is the file, if it's written to one, `source` the cause
+    Write {
+        path: Option<PathBuf>,
+        source: IoError,
+    },
+    /// a download failed, with the status of the response if there was one
+    Http(Option<u16>),
+    /// tiles don't cover the same location at the same resolution
+    TileMismatch,
+    /// an archive couldn't be read, or doesn't contain a `.hgt` file
+    Archive,
+    /// the bounding box of a [`PartialTile`] isn't south-west to north-east, or it has less than 2 rows or columns
+    InvalidBounds,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound { path, .. } => {
+                write!(f, "tile file not found: {}", path.display())
+            }
+            Error::ParseLatLong => write!(f, "couldn't parse latitude and longitude"),
+            Error::FilenameLength => write!(f, "filename is too short or too long for a tile"),
+            Error::FilenameHemisphere => {
+                write!(f, "filename doesn't start with N or S, followed by E or W")
+            }
+            Error::FilenameDigits => {
+                write!(
+                    f,
+                    "filename doesn't have 1 to 3 digits after each hemisphere"
+                )
+            }
+            Error::Filesize { path: None } => write!(f, "size doesn't match any known resolution"),
+            Error::Filesize { path: Some(path) } => {
+                write!(
+                    f,
+                    "size of {} doesn't match any known resolution",
+                    path.display()
+                )
+            }
+            Error::Read { path: None, .. } => write!(f, "couldn't read elevation data"),
+            Error::Read {
+                path: Some(path), ..
+            } => write!(f, "couldn't read elevation data from {}", path.display()),
+            Error::ImplausibleData => write!(f, "elevation data is implausible"),
+            Error::Write { path: None, .. } => write!(f, "couldn't write output"),
+            Error::Write {
+                path: Some(path), ..
+            } => write!(f, "couldn't write output to {}", path.display()),
+            Error::Http(Some(status)) => write!(f, "download failed with HTTP status {status}"),
+            Error::Http(None) => write!(f, "download failed"),
+            Error::TileMismatch => write!(f, "tiles don't match in location or resolution"),
+            Error::Archive => write!(f, "archive doesn't contain a readable .hgt file"),
+            Error::InvalidBounds => write!(f, "bounding box or number of samples is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NotFound { source, .. }
+            | Error::Write { source, .. }
+            | Error::Read {
+                source: Some(source),
+                ..
+            } => Some(source.get_ref()),
+            _ => None,
+        }
+    }
+}
+
+// impl for non-pub fn-s
+impl Error {
+    /// the file at `path` couldn't be opened because of `source`:
+    /// [`Error::NotFound`] if it doesn't exist, [`Error::Read`] for anything else, eg. a lack of permissions
+    pub(crate) fn open(path: impl AsRef<Path>, source: io::Error) -> Error {
+        let path = path.as_ref().to_path_buf();
+        match source.kind() {
+            io::ErrorKind::NotFound => Error::NotFound {
+                path,
+                source: source.into(),
+            },
+            _ => Error::Read {
+                path: Some(path),
+                source: Some(source.into()),
+            },
+        }
+    }
+    /// [`Error::Filesize`] without a file
+    pub(crate) const fn filesize() -> Error {
+        Error::Filesize { path: None }
+    }
+    /// [`Error::Read`] without a file, because of `source`
+    pub(crate) fn read(source: io::Error) -> Error {
+        Error::Read {
+            path: None,
+            source: Some(source.into()),
+        }
+    }
+    /// [`Error::Write`] without a file, because of `source`
+    #[cfg(feature = "png")]
+    pub(crate) fn write(source: io::Error) -> Error {
+        Error::Write {
+            path: None,
+            source: source.into(),
+        }
+    }
+    /// attach `path` to an [`Error::Filesize`], [`Error::Read`] or [`Error::Write`] that doesn't name a file yet
+    pub(crate) fn in_file(self, path: impl AsRef<Path>) -> Error {
+        let path = Some(path.as_ref().to_path_buf());
+        match self {
+            Error::Filesize { path: None } => Error::Filesize { path },
+            Error::Read { path: None, source } => Error::Read { path, source },
+            Error::Write { path: None, source } => Error::Write { path, source },
+            e => e,
+        }
+    }
+}
+
+/// an [`io::Error`] that can be cloned and compared, as part of an [`Error`]
+/// errors are equal if they are of the same [`io::ErrorKind`]
+#[derive(Debug, Clone)]
+pub struct IoError(Arc<io::Error>);
+
+impl IoError {
+    /// the kind of the underlying [`io::Error`]
+    pub fn kind(&self) -> io::ErrorKind {
+        self.0.kind()
+    }
+    /// the underlying [`io::Error`]
+    pub fn get_ref(&self) -> &io::Error {
+        &self.0
+    }
+}
+
+impl From<io::Error> for IoError {
+    fn from(e: io::Error) -> Self {
+        Self(Arc::new(e))
+    }
+}
+impl From<io::ErrorKind> for IoError {
+    fn from(kind: io::ErrorKind) -> Self {
+        io::Error::from(kind).into()
+    }
+}
+
+impl PartialEq for IoError {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind() == other.kind()
+    }
+}
+impl Eq for IoError {}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
 }
 
 pub trait HgtReader {
@@ -47,3 +247,215 @@ pub trait HgtReader {
     fn read_hgt_data<FILE>(&self, file: FILE, res: Resolution) -> Result<Vec<i16>, Error>;
     fn close_hgt_data<FILE>(&self, file: FILE) -> Result<(), Error>;
 }
+
+/// get the elevation of `coord`, loading the [`Tile`] that contains it from `data_dir`
+///
+/// loaded tiles are kept in memory in a [`SharedTileSet`] per `data_dir`, so later calls that fall into them don't read the files again,
+/// while a tile is loaded, calls for tiles that are loaded already aren't blocked
+/// the tiles are kept for the rest of the program, use a [`SharedTileSet`] directly to be able to drop them
+///
+/// # Usage
+///
+/// ```rust
+/// let elevation = srtm_reader::elevation_at((44.4480403, 15.0733053), ".").unwrap();
+/// assert_eq!(elevation, Some(260));
+/// ```
+pub fn elevation_at<P: AsRef<Path>>(
+    coord: impl Into<Coord>,
+    data_dir: P,
+) -> Result<Option<i16>, Error> {
+    static TILE_SETS: Mutex<BTreeMap<PathBuf, Arc<SharedTileSet>>> = Mutex::new(BTreeMap::new());
+
+    let tile_set = Arc::clone(
+        TILE_SETS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(data_dir.as_ref().to_path_buf())
+            .or_insert_with_key(|dir| Arc::new(SharedTileSet::new(dir))),
+    );
+    tile_set.elevation(coord)
+}
+
+/// read the elevation of `coord` from the `.hgt` file at `path`, without loading the whole [`Tile`]
+///
+/// only the two bytes holding `coord`'s elevation are read, which is a lot cheaper
+/// than [`Tile::from_file()`] when just a few points are needed from a file
+/// `None` if the file doesn't contain `coord` or it's a void
+pub fn read_elevation_at<P: AsRef<Path>>(
+    path: P,
+    coord: impl Into<Coord>,
+) -> Result<Option<i16>, Error> {
+    let coord = coord.into();
+    let file = File::open(&path).map_err(|e| Error::open(&path, e))?;
+    let f_len = file
+        .metadata()
+        .map_err(|e| Error::read(e).in_file(&path))?
+        .len();
+    let res = Resolution::try_from(f_len).map_err(|_| Error::filesize().in_file(&path))?;
+
+    // `tile_key()` would clamp coordinates out of range onto the edge tiles
+    if Coord::opt_new(coord.lat, coord.lon).is_none()
+        || Tile::get_lat_lon(&path)? != coord.tile_key()
+    {
+        return Ok(None);
+    }
+    let Some(offset) = Tile::byte_offset_in(res, coord) else {
+        return Ok(None);
+    };
+    let elev = Tile::from_reader_at(file, offset).map_err(|e| Error::read(e).in_file(&path))?;
+
+    Ok(Some(elev).filter(|e| !tiles::is_void(*e)))
+}
+
+/// whether `dir` contains the file of the [`Tile`] including `coord`, plain or compressed:
+/// `.hgt`, `.hgt.gz` or `.hgt.zip`
+///
+/// the file isn't opened, let alone parsed
+pub fn tile_exists<P: AsRef<Path>>(coord: impl Into<Coord>, dir: P) -> bool {
+    let filename = coord.into().get_filename();
+    ["", ".gz", ".zip"]
+        .iter()
+        .any(|ext| dir.as_ref().join(format!("{filename}{ext}")).is_file())
+}
+
+/// check that the `.hgt` file at `path` is well-formed, without parsing it into a [`Tile`]
+///
+/// the filename has to contain a latitude and longitude, and the size has to match a [`Resolution`]
+/// if `stream`, the whole file is read too, in small chunks, to make sure it can be
+///
+/// # Errors
+/// - [`Error::FilenameLength`], [`Error::FilenameHemisphere`], [`Error::FilenameDigits`] or [`Error::ParseLatLong`] for a misnamed file
+/// - [`Error::NotFound`] if it can't be opened
+/// - [`Error::Filesize`] if it's truncated or of an unknown size
+/// - [`Error::Read`] if streaming fails or ends early
+pub fn validate_file<P: AsRef<Path>>(path: P, stream: bool) -> Result<Resolution, Error> {
+    let path = path.as_ref();
+    Tile::get_lat_lon(path)?;
+    let res = Resolution::of_file(path)?;
+    if stream {
+        let file = File::open(path).map_err(|e| Error::open(path, e))?;
+        let len = res.total_len() as u64 * 2;
+        let read = io::copy(&mut file.take(len), &mut io::sink())
+            .map_err(|e| Error::read(e).in_file(path))?;
+        if read != len {
+            return Err(Error::read(io::ErrorKind::UnexpectedEof.into()).in_file(path));
+        }
+    }
+    Ok(res)
+}
+
+/// the [`Coord::tile_key()`]s of all the tiles `path` crosses
+///
+/// segments between consecutive points are treated as straight lines in latitude/longitude,
+/// taking the shorter way around, so one from 179.5 to -179.5 crosses the antimeridian,
+/// and every tile a segment passes through is included, even if it only clips a corner
+pub fn tiles_along_path(path: &[Coord]) -> BTreeSet<(i8, i16)> {
+    let mut tiles = path.iter().map(Coord::tile_key).collect::<BTreeSet<_>>();
+    for segment in path.windows(2) {
+        let from = segment[0];
+        // `to`, with its longitude unwrapped to within 180 degrees of `from`
+        let to = Coord {
+            lat: segment[1].lat,
+            lon: from.lon + Coord::wrap_longitude(segment[1].lon - from.lon),
+        };
+        let at = |t: f64| {
+            Coord::normalize(
+                from.lat + (to.lat - from.lat) * t,
+                from.lon + (to.lon - from.lon) * t,
+            )
+        };
+        // where the segment crosses a whole degree of latitude or longitude
+        let crossings = |a: f64, b: f64| {
+            let (lo, hi) = (a.min(b), a.max(b));
+            (lo.ceil() as i32..=hi.floor() as i32)
+                .map(move |deg| (deg as f64 - a) / (b - a))
+                .filter(|t| t.is_finite())
+        };
+        let mut ts = crossings(from.lat, to.lat)
+            .chain(crossings(from.lon, to.lon))
+            .chain([0., 1.])
+            .collect::<Vec<_>>();
+        ts.sort_by(f64::total_cmp);
+        // each piece between two crossings lies within a single tile
+        tiles.extend(
+            ts.windows(2)
+                .filter_map(|t| at((t[0] + t[1]) / 2.))
+                .map(|coord| coord.tile_key()),
+        );
+    }
+    tiles
+}
+
+/// simplify `path` with Douglas-Peucker, keeping every point that deviates more than
+/// `horizontal_tol_m` meters sideways or `vertical_tol_m` meters in elevation from the simplified track
+///
+/// the elevation of the simplified track is interpolated linearly between its kept points,
+/// so a sharp climb or drop survives even if it's horizontally negligible
+/// points without elevation, or next to one, only count horizontally
+/// the first and last points are always kept
+pub fn simplify_with_elevation(
+    path: &[(Coord, Option<i16>)],
+    horizontal_tol_m: f64,
+    vertical_tol_m: f64,
+) -> Vec<(Coord, Option<i16>)> {
+    if path.len() < 3 {
+        return path.to_vec();
+    }
+    // how far past its tolerance a deviation is, 0 if within
+    let excess = |deviation: f64, tol: f64| {
+        if deviation <= tol {
+            0.
+        } else if tol > 0. {
+            deviation / tol
+        } else {
+            f64::INFINITY
+        }
+    };
+    let mut keep = vec![false; path.len()];
+    keep[0] = true;
+    keep[path.len() - 1] = true;
+    let mut segments = vec![(0, path.len() - 1)];
+    while let Some((first, last)) = segments.pop() {
+        let ((a, a_elev), (b, b_elev)) = (path[first], path[last]);
+        // local equirectangular projection around `a`, in meters
+        let scale_lon = ((a.lat + b.lat) / 2.).to_radians().cos();
+        let project = |c: Coord| {
+            (
+                (c.lon - a.lon).to_radians() * scale_lon * coords::EARTH_RADIUS,
+                (c.lat - a.lat).to_radians() * coords::EARTH_RADIUS,
+            )
+        };
+        let (bx, by) = project(b);
+        let length_sq = bx * bx + by * by;
+        let mut worst: Option<(usize, f64)> = None;
+        for (i, (p, p_elev)) in path.iter().enumerate().take(last).skip(first + 1) {
+            let (px, py) = project(*p);
+            let t = if length_sq > 0. {
+                ((px * bx + py * by) / length_sq).clamp(0., 1.)
+            } else {
+                0.
+            };
+            let horizontal = (px - t * bx).hypot(py - t * by);
+            let vertical = match (a_elev, *p_elev, b_elev) {
+                (Some(a_elev), Some(p_elev), Some(b_elev)) => {
+                    let expected = a_elev as f64 + (b_elev as f64 - a_elev as f64) * t;
+                    (p_elev as f64 - expected).abs()
+                }
+                _ => 0.,
+            };
+            let score = excess(horizontal, horizontal_tol_m).max(excess(vertical, vertical_tol_m));
+            if score > 0. && worst.is_none_or(|(_, max)| score > max) {
+                worst = Some((i, score));
+            }
+        }
+        if let Some((i, _)) = worst {
+            keep[i] = true;
+            segments.push((first, i));
+            segments.push((i, last));
+        }
+    }
+    path.iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| keep.then_some(*point))
+        .collect()
+}
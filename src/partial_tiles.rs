@@ -0,0 +1,96 @@
+use super::{tiles::is_void, Coord, Error, Tile};
+
+use std::{fs::File, path::Path};
+
+/// elevation data that doesn't cover a whole, 1° square [`crate::Tile`], eg. a coastal clip
+///
+/// samples are laid out as in `.hgt` files: row-major, starting from the north-west corner,
+/// with the first and last rows and columns lying exactly on the edges of the bounding box
+/// use [`crate::Tile`] for standard data, it's faster
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialTile {
+    /// south-west corner of the bounding box
+    pub sw: Coord,
+    /// north-east corner of the bounding box
+    pub ne: Coord,
+    /// number of samples in a column, north to south
+    pub rows: usize,
+    /// number of samples in a row, west to east
+    pub cols: usize,
+    pub data: Vec<i16>,
+}
+
+impl PartialTile {
+    /// create a [`PartialTile`] spanning from `sw` to `ne`, with `rows` * `cols` samples in `data`
+    ///
+    /// # Errors
+    /// - [`Error::InvalidBounds`] if `sw` isn't south-west of `ne`, either isn't finite, or there are less than 2 `rows` or `cols`
+    /// - [`Error::Filesize`] if `data` doesn't have `rows` * `cols` samples
+    pub fn new(
+        sw: Coord,
+        ne: Coord,
+        rows: usize,
+        cols: usize,
+        data: Vec<i16>,
+    ) -> Result<PartialTile, Error> {
+        let finite = [sw.lat, sw.lon, ne.lat, ne.lon]
+            .iter()
+            .all(|deg| deg.is_finite());
+        if !finite || sw.lat >= ne.lat || sw.lon >= ne.lon || rows < 2 || cols < 2 {
+            return Err(Error::InvalidBounds);
+        }
+        if data.len() != rows * cols {
+            return Err(Error::filesize());
+        }
+        Ok(PartialTile {
+            sw,
+            ne,
+            rows,
+            cols,
+            data,
+        })
+    }
+
+    /// read a `.hgt` file with `rows` * `cols` samples, spanning from `sw` to `ne`
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        sw: Coord,
+        ne: Coord,
+        rows: usize,
+        cols: usize,
+    ) -> Result<PartialTile, Error> {
+        let file = File::open(&path).map_err(|e| Error::open(&path, e))?;
+        let f_len = file
+            .metadata()
+            .map_err(|e| Error::read(e).in_file(&path))?
+            .len();
+        if f_len != (rows * cols * 2) as u64 {
+            return Err(Error::filesize().in_file(&path));
+        }
+        let data =
+            Tile::parse_samples(file, rows, cols).map_err(|e| Error::read(e).in_file(&path))?;
+        Self::new(sw, ne, rows, cols, data)
+    }
+
+    /// whether `coord` is inside the bounding box of this [`PartialTile`]
+    pub fn contains(&self, coord: impl Into<Coord>) -> bool {
+        let coord = coord.into();
+        (self.sw.lat..=self.ne.lat).contains(&coord.lat)
+            && (self.sw.lon..=self.ne.lon).contains(&coord.lon)
+    }
+
+    /// get the elevation of the sample nearest to `coord`
+    /// `None` if `coord` is outside of this [`PartialTile`] or it's a void
+    pub fn get(&self, coord: impl Into<Coord>) -> Option<i16> {
+        let coord = coord.into();
+        if !self.contains(coord) {
+            return None;
+        }
+        let row_frac = (self.ne.lat - coord.lat) / (self.ne.lat - self.sw.lat);
+        let col_frac = (coord.lon - self.sw.lon) / (self.ne.lon - self.sw.lon);
+        let row = (row_frac * (self.rows - 1) as f64).round() as usize;
+        let col = (col_frac * (self.cols - 1) as f64).round() as usize;
+        let elev = *self.data.get(row * self.cols + col)?;
+        Some(elev).filter(|e| !is_void(*e))
+    }
+}
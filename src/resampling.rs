@@ -0,0 +1,159 @@
+//! resampling the elevation grid of a [`Tile`] onto other grids
+
+use super::{
+    tiles::{round_valid, CLAMP_TOLERANCE, VOID},
+    Coord, Resolution, Tile,
+};
+
+impl Tile {
+    /// the elevation at `coord`, interpolated bilinearly between the 4 samples of this [`Tile`] around it,
+    /// instead of taking the nearest one as [`Tile::get()`] does, so it's continuous along a track
+    ///
+    /// `None` if this [`Tile`] doesn't contain `coord`, or a sample needed is a void,
+    /// see [`Tile::get_interpolated_with_neighbors()`] to interpolate across the edges of tiles
+    pub fn get_interpolated(&self, coord: impl Into<Coord>) -> Option<f64> {
+        let coord = coord.into();
+        if !self.contains(coord) {
+            return None;
+        }
+        self.interpolate_at(coord)
+    }
+    /// the elevation at `coord`, interpolated bilinearly between the 4 samples around it
+    /// in whichever of `self` and `neighbors` contains `coord`, see [`Tile::contains()`]
+    ///
+    /// as adjacent tiles share their edge samples, the 4 samples always lie in that one tile,
+    /// so the result is continuous across seams, a `coord` on the shared edge of a tile not given
+    /// is read from the edge of the neighbour that is
+    ///
+    /// `None` if none of the tiles covers `coord`, or a sample needed is a void
+    pub fn get_interpolated_with_neighbors(
+        &self,
+        coord: impl Into<Coord>,
+        neighbors: &[&Tile],
+    ) -> Option<f64> {
+        let coord = coord.into();
+        let tiles = || std::iter::once(self).chain(neighbors.iter().copied());
+        match tiles().find(|tile| tile.contains(coord)) {
+            Some(tile) => tile.interpolate_at(coord),
+            None => tiles().find_map(|tile| tile.interpolate_at(coord)),
+        }
+    }
+
+    /// resample this [`Tile`] onto the grid of the finer `target` [`Resolution`], with bilinear interpolation
+    /// `None` if `target` isn't strictly finer than the resolution of this [`Tile`]
+    ///
+    /// with `fill_voids`, voids are left out of the interpolation and the remaining weights renormalized,
+    /// otherwise any void among the surrounding samples makes the new sample a void
+    pub fn upsample(&self, target: Resolution, fill_voids: bool) -> Option<Tile> {
+        if !target.is_finer_than(self.resolution) {
+            return None;
+        }
+        let extent = target.extent();
+        let data = (0..target.total_len())
+            .map(|i| self.upsampled_at(target, fill_voids, i / extent, i % extent))
+            .collect();
+        Some(
+            Tile::new(self.latitude, self.longitude, target, data)
+                .with_zero_as_void(self.zero_is_void()),
+        )
+    }
+
+    /// resample this [`Tile`] onto the grid of `target`: its location and [`Resolution`],
+    /// interpolating bilinearly between the samples of `self` around every sample of `target`
+    ///
+    /// the elevations of `target` aren't used, samples are [`VOID`] where `self` doesn't cover them,
+    /// eg. all but the shared edge of a neighbouring tile, or any sample of `self` needed is a void
+    pub fn regrid_onto(&self, target: &Tile) -> Tile {
+        let extent = target.resolution.extent();
+        let data = (0..target.resolution.total_len())
+            .map(|i| self.regridded_at(target, i / extent, i % extent))
+            .collect();
+        Tile::new(target.latitude, target.longitude, target.resolution, data)
+            .with_zero_as_void(self.zero_is_void())
+    }
+}
+
+// impl for non-pub fn-s
+impl Tile {
+    /// the sample at `row`, `col` of [`Tile::upsample()`] onto the grid of `target`
+    pub(crate) fn upsampled_at(
+        &self,
+        target: Resolution,
+        fill_voids: bool,
+        row: usize,
+        col: usize,
+    ) -> i16 {
+        let cells = self.resolution.cells_per_side();
+        let scale = cells as f64 / target.cells_per_side() as f64;
+        let extent = self.resolution.extent();
+        // the neighbouring source samples and the weight of the second one along an axis
+        let neighbours = |i: usize| {
+            let pos = i as f64 * scale;
+            let first = (pos.floor() as usize).min(cells);
+            ((first, (first + 1).min(cells)), pos - first as f64)
+        };
+        let ((r0, r1), fr) = neighbours(row);
+        let ((c0, c1), fc) = neighbours(col);
+        let samples = [
+            (r0, c0, (1. - fr) * (1. - fc)),
+            (r0, c1, (1. - fr) * fc),
+            (r1, c0, fr * (1. - fc)),
+            (r1, c1, fr * fc),
+        ];
+        let (mut sum, mut weights, mut has_void) = (0., 0., false);
+        for (r, c, weight) in samples.into_iter().filter(|s| s.2 > 0.) {
+            let elev = self.data[r * extent + c];
+            if self.treats_as_void(elev) {
+                has_void = true;
+            } else {
+                sum += elev as f64 * weight;
+                weights += weight;
+            }
+        }
+        if weights == 0. || (has_void && !fill_voids) {
+            VOID
+        } else {
+            round_valid(sum / weights, self.zero_is_void())
+        }
+    }
+    /// the sample at `row`, `col` of [`Tile::regrid_onto()`] `target`
+    pub(crate) fn regridded_at(&self, target: &Tile, row: usize, col: usize) -> i16 {
+        self.interpolate_at(target.coord_at(row, col))
+            .map_or(VOID, |elev| round_valid(elev, self.zero_is_void()))
+    }
+    /// the elevation at `coord` interpolated bilinearly between the samples of this [`Tile`] around it,
+    /// the edges included, up to [`CLAMP_TOLERANCE`] past them
+    /// `None` if `coord` is outside, or a sample with a weight is a void
+    fn interpolate_at(&self, coord: Coord) -> Option<f64> {
+        let cells = self.resolution.cells_per_side();
+        let (north, west) = (self.latitude as f64 + 1., self.longitude as f64);
+        let allowed = -CLAMP_TOLERANCE..=1. + CLAMP_TOLERANCE;
+        if !allowed.contains(&(north - coord.lat)) || !allowed.contains(&(coord.lon - west)) {
+            return None;
+        }
+        let row = ((north - coord.lat) * cells as f64).clamp(0., cells as f64);
+        let col = ((coord.lon - west) * cells as f64).clamp(0., cells as f64);
+        let (r0, c0) = (
+            (row.floor() as usize).min(cells - 1),
+            (col.floor() as usize).min(cells - 1),
+        );
+        let (fr, fc) = (row - r0 as f64, col - c0 as f64);
+        let extent = self.resolution.extent();
+        let mut value = 0.;
+        for (r, c, weight) in [
+            (r0, c0, (1. - fr) * (1. - fc)),
+            (r0, c0 + 1, (1. - fr) * fc),
+            (r0 + 1, c0, fr * (1. - fc)),
+            (r0 + 1, c0 + 1, fr * fc),
+        ] {
+            if weight > 0. {
+                let elev = self.data[r * extent + c];
+                if self.treats_as_void(elev) {
+                    return None;
+                }
+                value += elev as f64 * weight;
+            }
+        }
+        Some(value)
+    }
+}
@@ -1,8 +1,16 @@
-/// this many rows and columns are there in a standard SRTM1 file
+use crate::Error;
+
+use std::path::Path;
+
+/// this many intervals between samples are there along a side of a standard SRTM1 file
 const EXTENT: usize = 3600;
 
 /// the available resulutions of the SRTM data, in arc seconds
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Debug, Default)]
+///
+/// *NOTE*: the derived ordering follows the declaration order, so it doesn't tell which one is more precise,
+/// use [`Resolution::is_finer_than()`] to compare resolutions
+#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Resolution {
     SRTM05,
     #[default]
@@ -11,17 +19,56 @@ pub enum Resolution {
 }
 
 impl Resolution {
-    /// the number of rows and columns in an SRTM data file of [`Resolution`]
+    /// the number of rows and columns of samples in an SRTM data file of [`Resolution`]
+    /// same as [`Resolution::samples_per_side()`]
     pub const fn extent(&self) -> usize {
-        1 + match self {
+        self.samples_per_side()
+    }
+    /// the number of samples along each side of a [`crate::Tile`]: one more than [`Resolution::cells_per_side()`]
+    /// as the first and last rows and columns lie on the edges, shared with the neighbouring tiles
+    pub const fn samples_per_side(&self) -> usize {
+        1 + self.cells_per_side()
+    }
+    /// the number of intervals between samples along each side of a [`crate::Tile`], spanning 1 degree
+    pub const fn cells_per_side(&self) -> usize {
+        match self {
             Resolution::SRTM05 => EXTENT * 2,
             Resolution::SRTM1 => EXTENT,
             Resolution::SRTM3 => EXTENT / 3,
         }
     }
+    /// the distance between neighbouring samples, in arc seconds
+    pub const fn arc_seconds(&self) -> f64 {
+        match self {
+            Resolution::SRTM05 => 0.5,
+            Resolution::SRTM1 => 1.,
+            Resolution::SRTM3 => 3.,
+        }
+    }
+    /// whether `self` has samples closer to each other than `other`
+    pub fn is_finer_than(&self, other: Resolution) -> bool {
+        self.arc_seconds() < other.arc_seconds()
+    }
+    /// the distance between neighbouring samples, in degrees
+    pub fn cell_size_deg(&self) -> f64 {
+        1. / self.cells_per_side() as f64
+    }
+    /// the [`Resolution`] with `extent` rows and columns of samples, see [`Resolution::extent()`]
+    /// `None` if no resolution has that many
+    pub fn try_from_extent(extent: usize) -> Option<Resolution> {
+        [Resolution::SRTM05, Resolution::SRTM1, Resolution::SRTM3]
+            .into_iter()
+            .find(|res| res.extent() == extent)
+    }
+    /// the [`Resolution`] of the `.hgt` file at `path`, from its size, without reading it
+    pub fn of_file<P: AsRef<Path>>(path: P) -> Result<Resolution, Error> {
+        let path = path.as_ref();
+        let metadata = std::fs::metadata(path).map_err(|e| Error::open(path, e))?;
+        Resolution::try_from(metadata.len()).map_err(|_| Error::filesize().in_file(path))
+    }
     /// total file length in BigEndian, total file length in bytes is [`Resolution::total_len()`] * 2
     pub const fn total_len(&self) -> usize {
-        self.extent().pow(2)
+        self.samples_per_side().pow(2)
     }
 }
 
@@ -37,7 +84,6 @@ impl TryFrom<u64> for Resolution {
         } else if len == Resolution::SRTM3.total_len() * 2 {
             Ok(Resolution::SRTM3)
         } else {
-            eprintln!("unknown filesize: {len}");
             Err(())
         }
     }
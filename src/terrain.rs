@@ -0,0 +1,317 @@
+//! terrain analysis on the elevation grid of a [`Tile`]
+
+use super::{coords::EARTH_RADIUS, tiles, Coord, Tile};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// the value of cells without shading in [`Tile::multidirectional_hillshade()`]
+pub const HILLSHADE_VOID: u8 = 0;
+
+/// the weighting of the neighbours in [`Tile::smooth()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SmoothKind {
+    /// all cells of the window weigh the same
+    Box,
+    /// cells weigh less the further they are, with a standard deviation of half the radius
+    Gaussian,
+}
+
+impl Tile {
+    /// the aspect of every cell of this [`Tile`], in row-major order, as [`Tile::data`]
+    ///
+    /// aspect is the compass direction the slope faces (downhill), in degrees clockwise from north, in `0..360`
+    /// it's computed with Horn's method over the 3x3 window around each cell
+    ///
+    /// cells are `NaN` where aspect isn't defined:
+    /// - on the edges of the [`Tile`], since the window would stick out of it
+    /// - where any cell of the window is a void
+    /// - on flat terrain
+    pub fn aspect_grid(&self) -> Vec<f32> {
+        let extent = self.resolution.extent();
+        let mut grid = vec![f32::NAN; self.data.len()];
+        let fill_row = |(row, cells): (usize, &mut [f32])| {
+            let (dx, dy) = self.cell_size_m(row);
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let Some((dz_dx, dz_dy)) = self.gradient(row, col, dx, dy) else {
+                    continue;
+                };
+                if dz_dx == 0. && dz_dy == 0. {
+                    continue;
+                }
+                // downhill is against the gradient, `dz_dy` points north
+                let aspect = (-dz_dx).atan2(-dz_dy).to_degrees().rem_euclid(360.);
+                *cell = aspect as f32;
+            }
+        };
+        #[cfg(feature = "rayon")]
+        grid.par_chunks_mut(extent).enumerate().for_each(fill_row);
+        #[cfg(not(feature = "rayon"))]
+        grid.chunks_mut(extent).enumerate().for_each(fill_row);
+        grid
+    }
+    /// the D8 flow direction of every cell of this [`Tile`], in row-major order, as [`Tile::data`]
+    ///
+    /// it's the neighbour of steepest descent, the drop per distance, counting clockwise from east:
+    /// `1` east, `2` south-east, `3` south, `4` south-west, `5` west, `6` north-west, `7` north, `8` north-east
+    /// on ties, the first of them wins
+    ///
+    /// cells are `0` where water doesn't flow anywhere:
+    /// - on the edges of the [`Tile`], and where any cell of the 3x3 window is a void
+    /// - in sinks and on flat terrain, where no neighbour is lower
+    pub fn flow_direction(&self) -> Vec<u8> {
+        // the row and column offsets of the neighbours in the order of their codes
+        const NEIGHBOURS: [(usize, usize); 8] = [
+            (1, 2),
+            (2, 2),
+            (2, 1),
+            (2, 0),
+            (1, 0),
+            (0, 0),
+            (0, 1),
+            (0, 2),
+        ];
+        let extent = self.resolution.extent();
+        let mut grid = vec![0; self.data.len()];
+        let fill_row = |(row, cells): (usize, &mut [u8])| {
+            let (dx, dy) = self.cell_size_m(row);
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let Some(window) = self.window(row, col) else {
+                    continue;
+                };
+                let mut steepest = 0.;
+                for (code, (r, c)) in (1..).zip(NEIGHBOURS) {
+                    let distance = match (r == 1, c == 1) {
+                        (true, _) => dx,
+                        (_, true) => dy,
+                        _ => dx.hypot(dy),
+                    };
+                    let slope = (window[1][1] - window[r][c]) / distance;
+                    if slope > steepest {
+                        steepest = slope;
+                        *cell = code;
+                    }
+                }
+            }
+        };
+        #[cfg(feature = "rayon")]
+        grid.par_chunks_mut(extent).enumerate().for_each(fill_row);
+        #[cfg(not(feature = "rayon"))]
+        grid.chunks_mut(extent).enumerate().for_each(fill_row);
+        grid
+    }
+    /// apply `kernel` to the 3x3 window around every cell of this [`Tile`], in row-major order, as [`Tile::data`]
+    /// the kernel is laid out as the grid: its first row is to the north, its first column to the west
+    ///
+    /// cells are `None` on the edges of the [`Tile`], and where any cell of the window is a void
+    pub fn convolve3x3(&self, kernel: &[[f64; 3]; 3]) -> Vec<Option<f64>> {
+        let extent = self.resolution.extent();
+        (0..self.data.len())
+            .map(|i| {
+                let window = self.window(i / extent, i % extent)?;
+                Some(
+                    window
+                        .iter()
+                        .flatten()
+                        .zip(kernel.iter().flatten())
+                        .map(|(elev, weight)| elev * weight)
+                        .sum(),
+                )
+            })
+            .collect()
+    }
+    /// smooth this [`Tile`] in place: every elevation becomes the weighted mean of the valid ones
+    /// within `radius` rows and columns of it, weights renormalized over the valid cells, by the original grid
+    ///
+    /// voids stay voids, see [`Tile::fill_voids()`] to fill them
+    pub fn smooth(&mut self, kind: SmoothKind, radius: usize) {
+        let extent = self.resolution.extent();
+        let sigma = (radius as f64 / 2.).max(0.5);
+        let weight = |dr: usize, dc: usize| match kind {
+            SmoothKind::Box => 1.,
+            SmoothKind::Gaussian => (-((dr * dr + dc * dc) as f64) / (2. * sigma * sigma)).exp(),
+        };
+        let smoothed: Vec<i16> = (0..self.data.len())
+            .map(|i| {
+                let elev = self.data[i];
+                if self.treats_as_void(elev) {
+                    return elev;
+                }
+                let (row, col) = (i / extent, i % extent);
+                let (mut sum, mut weights) = (0., 0.);
+                for r in row.saturating_sub(radius)..=(row + radius).min(extent - 1) {
+                    for c in col.saturating_sub(radius)..=(col + radius).min(extent - 1) {
+                        let elev = self.data[r * extent + c];
+                        if !self.treats_as_void(elev) {
+                            let w = weight(r.abs_diff(row), c.abs_diff(col));
+                            sum += elev as f64 * w;
+                            weights += w;
+                        }
+                    }
+                }
+                tiles::round_valid(sum / weights, self.zero_is_void())
+            })
+            .collect();
+        self.data = smoothed;
+    }
+    /// the shading of every cell of this [`Tile`] lit from `altitude_deg` above the horizon,
+    /// averaged over sources in the north-west quadrant: 225°, 270°, 315° and 360°, in row-major order, as [`Tile::data`]
+    ///
+    /// softens the single direction bias of ordinary hillshading, for relief maps
+    /// lit terrain is in `1..=255`, cells are [`HILLSHADE_VOID`] where the slope isn't defined, as in [`Tile::aspect_grid()`]
+    pub fn multidirectional_hillshade(&self, altitude_deg: f64) -> Vec<u8> {
+        const AZIMUTHS: [f64; 4] = [225., 270., 315., 360.];
+        let extent = self.resolution.extent();
+        let mut grid = vec![HILLSHADE_VOID; self.data.len()];
+        let fill_row = |(row, cells): (usize, &mut [u8])| {
+            let (dx, dy) = self.cell_size_m(row);
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let Some((dz_dx, dz_dy)) = self.gradient(row, col, dx, dy) else {
+                    continue;
+                };
+                let shade = AZIMUTHS
+                    .iter()
+                    .map(|azimuth| illumination(dz_dx, dz_dy, *azimuth, altitude_deg))
+                    .sum::<f64>()
+                    / AZIMUTHS.len() as f64;
+                *cell = 1 + (shade * 254.).round() as u8;
+            }
+        };
+        #[cfg(feature = "rayon")]
+        grid.par_chunks_mut(extent).enumerate().for_each(fill_row);
+        #[cfg(not(feature = "rayon"))]
+        grid.chunks_mut(extent).enumerate().for_each(fill_row);
+        grid
+    }
+    /// the relative direct sunlight `coord` receives over a day at the equinox, in `0..=1`,
+    /// with the sun's path computed for `latitude_band` degrees, typically the latitude of `coord`
+    ///
+    /// it's the mean illumination of the terrain, as in a hillshade, from a dozen sun positions
+    /// evenly spaced in time between sunrise and sunset: `1` only for terrain facing the sun all day at the equator
+    /// shadows cast by surrounding terrain aren't considered, so it's meant for comparing cells, eg. for siting
+    ///
+    /// `None` if this [`Tile`] doesn't contain `coord`, or the slope isn't defined there, as in [`Tile::aspect_grid()`]
+    pub fn insolation(&self, coord: impl Into<Coord>, latitude_band: f64) -> Option<f64> {
+        const SUN_POSITIONS: usize = 12;
+        let coord = coord.into();
+        if !self.contains(coord) {
+            return None;
+        }
+        let cells = self.resolution.cells_per_side() as f64;
+        let row = ((self.latitude as f64 + 1. - coord.lat) * cells).round() as usize;
+        let col = ((coord.lon - self.longitude as f64) * cells).round() as usize;
+        let (dx, dy) = self.cell_size_m(row);
+        let (dz_dx, dz_dy) = self.gradient(row, col, dx, dy)?;
+        let lat = latitude_band.to_radians();
+        let total: f64 = (0..SUN_POSITIONS)
+            .map(|i| {
+                // the hour angle, from sunrise at -90° to sunset at 90°, as the declination is 0
+                let hour = (-90. + 180. * (i as f64 + 0.5) / SUN_POSITIONS as f64).to_radians();
+                // towards the sun: east, north, up
+                let (east, north, up) =
+                    (-hour.sin(), -lat.sin() * hour.cos(), lat.cos() * hour.cos());
+                let azimuth = east.atan2(north).to_degrees();
+                let altitude = up.asin().to_degrees();
+                illumination(dz_dx, dz_dy, azimuth, altitude)
+            })
+            .sum();
+        Some(total / SUN_POSITIONS as f64)
+    }
+    /// the ground area of the valid elevations higher than `threshold`, in km²,
+    /// the area weighted version of [`Tile::count_above()`]
+    ///
+    /// each sample counts for a cell of one interval between samples in both directions,
+    /// narrowing towards the poles, terrain slope isn't considered
+    pub fn area_above_km2(&self, threshold: i16) -> f64 {
+        let extent = self.resolution.extent();
+        let area_m2: f64 = self
+            .data
+            .chunks(extent)
+            .enumerate()
+            .map(|(row, elevations)| {
+                let (dx, dy) = self.cell_size_m(row);
+                let above = elevations
+                    .iter()
+                    .filter(|elev| !self.treats_as_void(**elev) && **elev > threshold)
+                    .count();
+                above as f64 * dx * dy
+            })
+            .sum();
+        area_m2 / 1e6
+    }
+    /// same as [`Tile::aspect_grid()`], but runs on `pool` instead of the global rayon thread pool
+    #[cfg(feature = "rayon")]
+    pub fn aspect_grid_in(&self, pool: &rayon::ThreadPool) -> Vec<f32> {
+        pool.install(|| self.aspect_grid())
+    }
+    /// same as [`Tile::flow_direction()`], but runs on `pool` instead of the global rayon thread pool
+    #[cfg(feature = "rayon")]
+    pub fn flow_direction_in(&self, pool: &rayon::ThreadPool) -> Vec<u8> {
+        pool.install(|| self.flow_direction())
+    }
+    /// same as [`Tile::multidirectional_hillshade()`], but runs on `pool` instead of the global rayon thread pool
+    #[cfg(feature = "rayon")]
+    pub fn multidirectional_hillshade_in(
+        &self,
+        altitude_deg: f64,
+        pool: &rayon::ThreadPool,
+    ) -> Vec<u8> {
+        pool.install(|| self.multidirectional_hillshade(altitude_deg))
+    }
+}
+
+// impl for non-pub fn-s
+impl Tile {
+    /// the 3x3 window of elevations around `row`, `col`
+    /// `None` on the edges, or if any of them is a void
+    fn window(&self, row: usize, col: usize) -> Option<[[f64; 3]; 3]> {
+        let extent = self.resolution.extent();
+        if row == 0 || col == 0 || row + 1 >= extent || col + 1 >= extent {
+            return None;
+        }
+        let mut window = [[0.; 3]; 3];
+        for (dr, window_row) in window.iter_mut().enumerate() {
+            for (dc, cell) in window_row.iter_mut().enumerate() {
+                let elev = self.data[(row + dr - 1) * extent + col + dc - 1];
+                if self.treats_as_void(elev) {
+                    return None;
+                }
+                *cell = elev as f64;
+            }
+        }
+        Some(window)
+    }
+    /// the east-west and north-south distance between neighbouring cells in `row`, in meters
+    fn cell_size_m(&self, row: usize) -> (f64, f64) {
+        let cell_size = self.resolution.cell_size_deg();
+        let lat = self.latitude as f64 + 1. - row as f64 * cell_size;
+        let dy = (cell_size.to_radians()) * EARTH_RADIUS;
+        (dy * lat.to_radians().cos(), dy)
+    }
+    /// the rate of change of elevation towards east and north at `row`, `col`, with Horn's method
+    fn gradient(&self, row: usize, col: usize, dx: f64, dy: f64) -> Option<(f64, f64)> {
+        let [[a, b, c], [d, _, f], [g, h, i]] = self.window(row, col)?;
+        let dz_dx = ((c + 2. * f + i) - (a + 2. * d + g)) / (8. * dx);
+        let dz_dy = ((a + 2. * b + c) - (g + 2. * h + i)) / (8. * dy);
+        Some((dz_dx, dz_dy))
+    }
+}
+
+/// how directly the sun at `azimuth_deg` clockwise from north and `altitude_deg` above the horizon
+/// shines on terrain with the gradient `dz_dx`, `dz_dy`, the cosine of the angle of incidence, in `0..=1`
+/// `0` if the terrain faces away from the sun, or the sun is below the horizon
+fn illumination(dz_dx: f64, dz_dy: f64, azimuth_deg: f64, altitude_deg: f64) -> f64 {
+    if altitude_deg <= 0. {
+        return 0.;
+    }
+    let (azimuth, altitude) = (azimuth_deg.to_radians(), altitude_deg.to_radians());
+    let sun = (
+        altitude.cos() * azimuth.sin(),
+        altitude.cos() * azimuth.cos(),
+        altitude.sin(),
+    );
+    // the surface normal is (-dz_dx, -dz_dy, 1), normalized
+    let incidence =
+        (-dz_dx * sun.0 - dz_dy * sun.1 + sun.2) / (dz_dx * dz_dx + dz_dy * dz_dy + 1.).sqrt();
+    incidence.max(0.)
+}
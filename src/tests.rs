@@ -1,5 +1,20 @@
 use super::*;
-use std::path::Path;
+use std::{
+    collections::BTreeSet,
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// an SRTM3 [`Tile`] at N44E015, with each elevation given by `f(row, col)`
+fn synthetic_tile(f: impl Fn(usize, usize) -> i16) -> Tile {
+    let res = Resolution::SRTM3;
+    let extent = res.extent();
+    let data = (0..res.total_len())
+        .map(|i| f(i / extent, i % extent))
+        .collect();
+    Tile::new(44, 15, res, data)
+}
 
 #[test]
 fn parse_latidute_and_longitude() {
@@ -14,6 +29,44 @@ fn parse_latidute_and_longitude() {
 
     let sw = Path::new("S35W138.hgt");
     assert_eq!(Tile::get_lat_lon(sw).unwrap(), (-35, -138));
+
+    let bare = Path::new("/tmp/N35E138");
+    assert_eq!(Tile::get_lat_lon(bare).unwrap(), (35, 138));
+
+    let gz = Path::new("N35E138.hgt.gz");
+    assert_eq!(Tile::get_lat_lon(gz).unwrap(), (35, 138));
+}
+#[test]
+fn parse_unpadded_latitude_and_longitude() {
+    assert_eq!(Tile::get_lat_lon("N5E5.hgt"), Ok((5, 5)));
+    assert_eq!(Tile::get_lat_lon("N05E005.hgt"), Ok((5, 5)));
+    assert_eq!(Tile::get_lat_lon("S5W15"), Ok((-5, -15)));
+    assert_eq!(Tile::get_lat_lon("n44e015.hgt"), Ok((44, 15)));
+    assert_eq!(Tile::get_lat_lon("N044E15.hgt"), Ok((44, 15)));
+    assert_eq!(Tile::get_lat_lon("S90W180.hgt"), Ok((-90, -180)));
+    assert_eq!(Tile::get_lat_lon("N89E179.hgt"), Ok((89, 179)));
+
+    for (malformed, err) in [
+        ("", Error::FilenameLength),
+        ("N44.hgt", Error::FilenameLength),
+        ("N44E015x.hgt", Error::FilenameLength),
+        ("E15N44.hgt", Error::FilenameHemisphere),
+        ("X44E015.hgt", Error::FilenameHemisphere),
+        ("N4X015.hgt", Error::FilenameHemisphere),
+        ("elevations", Error::FilenameHemisphere),
+        ("N44E.hgt", Error::FilenameDigits),
+        ("NE015.hgt", Error::FilenameDigits),
+        ("NX4E015.hgt", Error::FilenameDigits),
+        ("N44E0150.hgt", Error::FilenameDigits),
+        ("N91E015.hgt", Error::ParseLatLong),
+        ("N44E181.hgt", Error::ParseLatLong),
+        ("N90E000.hgt", Error::ParseLatLong),
+        ("N00E180.hgt", Error::ParseLatLong),
+        ("S91E000.hgt", Error::ParseLatLong),
+        ("N00W181.hgt", Error::ParseLatLong),
+    ] {
+        assert_eq!(Tile::get_lat_lon(malformed), Err(err), "{malformed}");
+    }
 }
 #[test]
 fn total_file_sizes() {
@@ -27,6 +80,17 @@ fn extents() {
     assert_eq!(3601, Resolution::SRTM1.extent());
     assert_eq!(1201, Resolution::SRTM3.extent());
 }
+#[test]
+fn samples_and_cells_per_side() {
+    for res in [Resolution::SRTM05, Resolution::SRTM1, Resolution::SRTM3] {
+        assert_eq!(res.samples_per_side(), res.extent());
+        assert_eq!(res.samples_per_side(), res.cells_per_side() + 1);
+        assert_eq!(res.cell_size_deg() * res.cells_per_side() as f64, 1.);
+    }
+    assert_eq!(3601, Resolution::SRTM1.samples_per_side());
+    assert_eq!(3600, Resolution::SRTM1.cells_per_side());
+    assert_eq!(1. / 1200., Resolution::SRTM3.cell_size_deg());
+}
 
 #[test]
 fn wrong_coords() {
@@ -70,9 +134,70 @@ fn coords() -> [Coord; 3] {
 fn file_names() {
     let fnames = coords()
         .iter()
-        .map(|c| Coord::from(*c).get_filename())
+        .map(|c| c.get_filename())
         .collect::<Vec<_>>();
-    assert_eq!(fnames, ["N45E001.hgt", "S02E087.hgt", "N35W007.hgt"]);
+    // -2.3 lies between -3 and -2, in the tile whose south-west corner is S03
+    assert_eq!(fnames, ["N45E001.hgt", "S03E087.hgt", "N35W007.hgt"]);
+}
+#[test]
+fn tile_selection_at_boundaries() {
+    let key = |lat: f64, lon: f64| Coord { lat, lon }.tile_key();
+    assert_eq!(key(0.0, 0.0), (0, 0));
+    assert_eq!(key(-0.0, 0.0), (0, 0));
+    assert_eq!(key(0.0, -0.0), (0, 0));
+    assert_eq!(key(0.9999, 0.9999), (0, 0));
+    assert_eq!(key(1.0, 1.0), (1, 1));
+    assert_eq!(key(-0.0001, -0.0001), (-1, -1));
+    assert_eq!(key(-1.0, -1.0), (-1, -1));
+    assert_eq!(key(-1.5, -179.5), (-2, -180));
+    assert_eq!(key(90.0, 180.0), (89, 179));
+    assert_eq!(key(-90.0, -180.0), (-90, -180));
+    assert_eq!(Coord::new(-0.0, -0.0).get_filename(), "N00E000.hgt");
+    assert_eq!(Coord::new(-0.5, -0.5).get_filename(), "S01W001.hgt");
+
+    // invalid coordinates are clamped onto the outermost tiles, and never found in them
+    assert_eq!(key(f64::NAN, f64::NAN), (0, 0));
+    assert_eq!(key(f64::INFINITY, f64::INFINITY), (89, 179));
+    assert_eq!(key(f64::NEG_INFINITY, f64::NEG_INFINITY), (-90, -180));
+    assert_eq!(key(1e9, 1e9), (89, 179));
+    assert_eq!(key(-1e9, -1e9), (-90, -180));
+    let mut corner = synthetic_tile(|_, _| 1);
+    for (lat, lon) in [(0, 0), (89, 179), (-90, -180)] {
+        (corner.latitude, corner.longitude) = (lat, lon);
+        let set = TileSet::from_iter([corner.clone()]);
+        for bad in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e9, -1e9] {
+            for coord in [(bad, 0.), (0., bad), (bad, bad)] {
+                assert_eq!(corner.lookup(coord), Lookup::OutsideTile);
+                assert_eq!(corner.raw_at(coord), None);
+                assert!(set.tile(coord).is_none());
+                assert_eq!(set.elevation(coord), None);
+                corner.for_coords(&[coord.into()], |_, elev| assert_eq!(elev, None));
+            }
+        }
+    }
+
+    // a sample on a shared boundary is read from the tile north-east of it, at its south-west corner
+    let mut tile = synthetic_tile(|row, col| (row * 2 + col) as i16);
+    (tile.latitude, tile.longitude) = (0, 0);
+    let extent = tile.resolution.extent();
+    assert!(tile.contains((0.0, 0.0)));
+    assert!(tile.contains((0.9999, 0.9999)));
+    assert!(!tile.contains((1.0, 1.0)));
+    assert!(!tile.contains((-0.0001, 0.5)));
+    assert_eq!(
+        tile.elevation((0.0, 0.0)),
+        Some(tile.data[(extent - 1) * extent])
+    );
+    assert_eq!(
+        tile.elevation((0.9999, 0.9999)),
+        Some(tile.data[extent - 1])
+    );
+    assert_eq!(tile.lookup((1.0, 1.0)), Lookup::OutsideTile);
+
+    let mut south_west = synthetic_tile(|_, _| 7);
+    (south_west.latitude, south_west.longitude) = (-1, -1);
+    assert!(south_west.contains((-0.5, -0.0001)));
+    assert_eq!(south_west.elevation((-0.5, -0.5)), Some(7));
 }
 #[test]
 fn read() {
@@ -85,5 +210,2342 @@ fn read() {
     assert_eq!(tile.data.len(), Resolution::SRTM1.total_len());
 
     let elev = tile.get(coord);
-    assert_eq!(elev, Some(&258));
+    assert_eq!(elev, Some(&260));
+}
+#[test]
+fn elevation_at_data_dir() {
+    let coord = Coord::new(44.4480403, 15.0733053);
+    assert_eq!(elevation_at(coord, ".").unwrap(), Some(260));
+    // served from the loaded tile
+    assert_eq!(elevation_at(coord, ".").unwrap(), Some(260));
+
+    // alternating between tiles doesn't read them again, even once the files are gone
+    let dir = std::env::temp_dir().join("srtm_reader_elevation_at_data_dir");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy("N44E015.hgt", dir.join("N44E015.hgt")).unwrap();
+    let mut north = synthetic_tile(|_, _| 1234);
+    north.latitude = 45;
+    std::fs::write(dir.join("N45E015.hgt"), north.as_be_bytes()).unwrap();
+    assert_eq!(elevation_at(coord, &dir).unwrap(), Some(260));
+    assert_eq!(elevation_at((45.5, 15.5), &dir).unwrap(), Some(1234));
+    std::fs::remove_dir_all(&dir).unwrap();
+    for _ in 0..2 {
+        assert_eq!(elevation_at(coord, &dir).unwrap(), Some(260));
+        assert_eq!(elevation_at((45.5, 15.5), &dir).unwrap(), Some(1234));
+    }
+
+    let missing = Coord::new(10.5, 10.5);
+    let not_found = Error::open(
+        Path::new(".").join("N10E010.hgt"),
+        io::ErrorKind::NotFound.into(),
+    );
+    assert_eq!(elevation_at(missing, "."), Err(not_found));
+}
+#[test]
+fn read_without_extension() {
+    let dir = std::env::temp_dir().join("srtm_reader_read_without_extension");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("N44E015");
+    std::fs::copy("N44E015.hgt", &path).unwrap();
+
+    let tile = Tile::from_file(&path).unwrap();
+    assert_eq!(tile.latitude, 44);
+    assert_eq!(tile.longitude, 15);
+    assert_eq!(tile.resolution, Resolution::SRTM1);
+    assert_eq!(tile.get(Coord::new(44.4480403, 15.0733053)), Some(&260));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn map_elevations() {
+    let tile = synthetic_tile(|row, col| {
+        if row == 3 && col == 7 {
+            tiles::VOID
+        } else {
+            ((row + col) % 3000) as i16
+        }
+    });
+    const FEET_PER_METER: f64 = 3.28084;
+    let feet = tile.mapped(|m| (m as f64 * FEET_PER_METER).round() as i16);
+    assert_eq!(feet.data[3 * tile.resolution.extent() + 7], tiles::VOID);
+    assert_eq!(feet.data[1], 3);
+
+    let mut meters = feet.clone();
+    meters.map_elevations(|ft| (ft as f64 / FEET_PER_METER).round() as i16);
+    for (orig, back) in tile.data.iter().zip(&meters.data) {
+        assert!((orig - back).abs() <= 1, "{orig} vs {back}");
+    }
+    assert_eq!(meters.data[3 * tile.resolution.extent() + 7], tiles::VOID);
+}
+#[test]
+fn byte_offset() {
+    let coord = Coord::new(44.4480403, 15.0733053);
+    let tile = Tile::from_file("N44E015.hgt").unwrap();
+    let offset = tile.byte_offset(coord).unwrap();
+    assert_eq!(Some(offset), Tile::byte_offset_in(Resolution::SRTM1, coord));
+    let file = std::fs::File::open("N44E015.hgt").unwrap();
+    assert_eq!(Tile::from_reader_at(file, offset).unwrap(), 260);
+
+    assert_eq!(tile.byte_offset((45.5, 15.5)), None);
+    assert_eq!(
+        tile.byte_offset((44.5, 15.5)),
+        Some(2 * (1800 * 3601 + 1800))
+    );
+
+    let res = Resolution::SRTM3;
+    let at = |lat: f64, lon: f64| Tile::byte_offset_in(res, Coord { lat, lon });
+    assert_eq!(at(f64::NAN, 15.5), None);
+    assert_eq!(at(44.5, f64::NAN), None);
+    // just beyond the outermost tiles, which `tile_key()` clamps to
+    assert_eq!(at(90.5, 15.5), None);
+    assert_eq!(at(90.0001, 15.5), None);
+    assert_eq!(at(-90.0001, 15.5), None);
+    assert_eq!(at(44.5, 180.0001), None);
+    assert_eq!(at(44.5, -180.0001), None);
+    assert_eq!(at(90., 15.5), Some(2 * 600));
+    assert_eq!(at(44.5, 180.), Some(2 * (600 * 1201 + 1200)));
+}
+#[test]
+fn read_elevation_at_matches_tile() {
+    let tile = Tile::from_file("N44E015.hgt").unwrap();
+    for coord in [
+        Coord::new(44.4480403, 15.0733053),
+        Coord::new(44.001, 15.999),
+        Coord::new(44.9, 15.1),
+        Coord::new(44.5, 15.5),
+    ] {
+        let elev = read_elevation_at("N44E015.hgt", coord).unwrap();
+        assert_eq!(elev, tile.elevation(coord), "{coord:?}");
+    }
+    assert_eq!(read_elevation_at("N44E015.hgt", (43.5, 15.5)), Ok(None));
+    let nowhere = Coord {
+        lat: f64::NAN,
+        lon: 15.5,
+    };
+    assert_eq!(read_elevation_at("N44E015.hgt", nowhere), Ok(None));
+
+    // clamped onto the northernmost tile, if not rejected
+    let dir = std::env::temp_dir().join("srtm_reader_read_elevation_at");
+    std::fs::create_dir_all(&dir).unwrap();
+    let polar = dir.join("N89E015.hgt");
+    std::fs::copy("N44E015.hgt", &polar).unwrap();
+    assert!(read_elevation_at(&polar, (89.5, 15.5)).unwrap().is_some());
+    for lat in [90.5, 95.] {
+        let beyond = Coord { lat, lon: 15.5 };
+        assert_eq!(read_elevation_at(&polar, beyond), Ok(None));
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn all_void_tile() {
+    let tile = synthetic_tile(|_, _| tiles::VOID);
+    assert!(tile.is_all_void());
+    assert_eq!(tile.min_height(), None);
+    assert_eq!(tile.max_height(), None);
+    assert_eq!(tile.get((44.5, 15.5)), None);
+
+    let mut tile = tile;
+    tile.data[42] = 17;
+    tile.data[43] = i16::MIN;
+    assert!(!tile.is_all_void());
+    assert_eq!(tile.min_height(), Some(17));
+    assert_eq!(tile.max_height(), Some(17));
+}
+#[test]
+fn tiles_along_path_corner() {
+    let path = [Coord::new(44.9, 15.5), Coord::new(45.5, 16.1)];
+    let tiles = tiles_along_path(&path);
+    assert_eq!(tiles, BTreeSet::from([(44, 15), (45, 15), (45, 16)]));
+
+    let single = tiles_along_path(&[Coord::new(44.5, 15.5)]);
+    assert_eq!(single, BTreeSet::from([(44, 15)]));
+    assert!(tiles_along_path(&[]).is_empty());
+}
+#[test]
+fn tiles_along_path_across_antimeridian() {
+    let path = [Coord::new(0., 179.5), Coord::new(0., -179.5)];
+    assert_eq!(
+        tiles_along_path(&path),
+        BTreeSet::from([(0, -180), (0, 179)])
+    );
+    let back = [path[1], path[0]];
+    assert_eq!(
+        tiles_along_path(&back),
+        BTreeSet::from([(0, -180), (0, 179)])
+    );
+}
+#[test]
+fn validate_byte_order() {
+    let tile = synthetic_tile(|row, col| ((row + col) % 3000) as i16);
+    let be = tile
+        .data
+        .iter()
+        .flat_map(|e| e.to_be_bytes())
+        .collect::<Vec<_>>();
+    let le = tile
+        .data
+        .iter()
+        .flat_map(|e| e.to_le_bytes())
+        .collect::<Vec<_>>();
+    let validate = ParseOptions { validate: true };
+
+    let parsed = Tile::parse_hgt_with(be.as_slice(), tile.resolution, validate).unwrap();
+    assert_eq!(parsed, tile.data);
+    let swapped = Tile::parse_hgt_with(le.as_slice(), tile.resolution, validate);
+    assert_eq!(swapped, Err(Error::ImplausibleData));
+    // the fast path doesn't look at the values
+    let unchecked = Tile::parse_hgt_with(le.as_slice(), tile.resolution, ParseOptions::default());
+    assert!(unchecked.is_ok());
+
+    assert!(Tile::from_file_with("N44E015.hgt", validate).is_ok());
+}
+#[test]
+fn tile_set_conflicts() {
+    let tile_at =
+        |lat, lon, res: Resolution, elev| Tile::new(lat, lon, res, vec![elev; res.total_len()]);
+    let mut base = TileSet::new();
+    base.insert(tile_at(44, 15, Resolution::SRTM3, 3));
+    base.insert(tile_at(45, 15, Resolution::SRTM1, 1));
+
+    let mut patch = TileSet::new();
+    patch.insert(tile_at(44, 15, Resolution::SRTM1, 10));
+    patch.insert(tile_at(45, 15, Resolution::SRTM3, 30));
+    patch.insert(tile_at(46, 15, Resolution::SRTM3, 31));
+
+    let merged = base.clone().merge(patch.clone());
+    assert_eq!(merged.len(), 3);
+    // finer resolution wins, regardless of order
+    assert_eq!(merged.elevation((44.5, 15.5)), Some(10));
+    assert_eq!(merged.elevation((45.5, 15.5)), Some(1));
+    assert_eq!(merged.elevation((46.5, 15.5)), Some(31));
+    assert_eq!(merged.elevation((47.5, 15.5)), None);
+
+    // on equal resolution, the last one wins
+    let mut set = patch;
+    let mut newer = TileSet::new();
+    newer.insert(tile_at(46, 15, Resolution::SRTM3, 32));
+    set.extend(newer);
+    assert_eq!(set.elevation((46.5, 15.5)), Some(32));
+}
+#[test]
+fn explicit_coord_order() {
+    assert_eq!(Coord::lat_lon(44.5, 15.2), Coord::new(44.5, 15.2));
+    assert_eq!(Coord::lon_lat(15.2, 44.5), Coord::new(44.5, 15.2));
+    assert_eq!(Coord::lon_lat(150., 44.5).lat, 44.5);
+}
+#[cfg(feature = "geo-types")]
+#[test]
+fn coord_from_geo_point() {
+    let point = geo_types::Point::new(15.2, 44.5);
+    assert_eq!(Coord::from(point), Coord::new(44.5, 15.2));
+}
+#[test]
+fn aspect_of_planar_slopes() {
+    let extent = Resolution::SRTM3.extent();
+    let assert_aspect = |tile: &Tile, expected: f32| {
+        let grid = tile.aspect_grid();
+        assert_eq!(grid.len(), tile.data.len());
+        for (i, aspect) in grid.iter().enumerate() {
+            let (row, col) = (i / extent, i % extent);
+            if row == 0 || col == 0 || row == extent - 1 || col == extent - 1 {
+                assert!(aspect.is_nan());
+            } else {
+                assert!((aspect - expected).abs() < 1e-3, "{row}, {col}: {aspect}");
+            }
+        }
+    };
+    // rising towards east, facing west
+    assert_aspect(&synthetic_tile(|_, col| col as i16), 270.);
+    // rising towards north, facing south
+    assert_aspect(&synthetic_tile(|row, _| (extent - row) as i16), 180.);
+    // rising towards south, facing north
+    assert_aspect(&synthetic_tile(|row, _| row as i16), 0.);
+
+    let flat = synthetic_tile(|_, _| 100);
+    assert!(flat.aspect_grid().iter().all(|a| a.is_nan()));
+}
+#[test]
+fn offset_uses_sample_intervals() {
+    let tile = Tile::from_file("N44E015.hgt").unwrap();
+    // Veli Brig, published elevation: 263m
+    let veli_brig = Coord::new(44.4480403, 15.0733053);
+    assert_eq!(tile.byte_offset(veli_brig), Some(2 * (1987 * 3601 + 264)));
+    assert_eq!(tile.get(veli_brig), Some(&260));
+
+    // the far edges of the tile are the last samples, not outside of it
+    let south_east = Coord::new(44.0000001, 15.9999999);
+    let last = Resolution::SRTM1.total_len() as u64 - 1;
+    assert_eq!(tile.byte_offset(south_east), Some(2 * last));
+    assert_eq!(tile.get(south_east), tile.data.last());
+    assert_eq!(tile.byte_offset((44.9999999, 15.0000001)), Some(0));
+}
+#[test]
+fn partial_tile() {
+    // 0.5° north-south, 0.25° east-west, a sample every 0.125°: 5 rows, 3 columns
+    let (sw, ne) = (Coord::new(44., 15.), Coord::new(44.5, 15.25));
+    let data = (0..15).collect::<Vec<i16>>();
+    assert_eq!(
+        PartialTile::new(sw, ne, 5, 3, data[..14].to_vec()),
+        Err(Error::filesize())
+    );
+    assert_eq!(
+        PartialTile::new(ne, sw, 5, 3, data.clone()),
+        Err(Error::InvalidBounds)
+    );
+    assert_eq!(
+        PartialTile::new(sw, ne, 1, 15, data.clone()),
+        Err(Error::InvalidBounds)
+    );
+    for corrupt in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let nowhere = Coord {
+            lat: corrupt,
+            lon: corrupt,
+        };
+        for (sw, ne) in [
+            (nowhere, ne),
+            (sw, nowhere),
+            (Coord { lon: corrupt, ..sw }, ne),
+        ] {
+            assert_eq!(
+                PartialTile::new(sw, ne, 5, 3, data.clone()),
+                Err(Error::InvalidBounds)
+            );
+        }
+    }
+    let mut tile = PartialTile::new(sw, ne, 5, 3, data).unwrap();
+    tile.data[7] = tiles::VOID;
+
+    assert_eq!(tile.get(ne.with_lon(15.)), Some(0));
+    assert_eq!(tile.get(ne), Some(2));
+    assert_eq!(tile.get(sw), Some(12));
+    assert_eq!(tile.get((44.38, 15.01)), Some(3));
+    assert_eq!(tile.get((44.25, 15.125)), None);
+    assert_eq!(tile.get((44.6, 15.1)), None);
+    assert!(!tile.contains((44.2, 15.3)));
+
+    let path = std::env::temp_dir().join("srtm_reader_partial_tile.hgt");
+    let bytes: Vec<u8> = tile.data.iter().flat_map(|e| e.to_be_bytes()).collect();
+    std::fs::write(&path, &bytes).unwrap();
+    assert_eq!(PartialTile::from_file(&path, sw, ne, 5, 3), Ok(tile));
+    assert_eq!(
+        PartialTile::from_file(&path, sw, ne, 3, 3),
+        Err(Error::filesize().in_file(&path))
+    );
+    std::fs::write(&path, &bytes[..4]).unwrap();
+    assert_eq!(
+        PartialTile::from_file(&path, ne, sw, 1, 2),
+        Err(Error::InvalidBounds)
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+#[test]
+fn set_voids_to() {
+    let mut tile = synthetic_tile(|_, col| col as i16 - 1);
+    tile.data[2] = tiles::VOID;
+    tile.data[3] = i16::MIN;
+    tile.set_voids_to(0);
+    assert_eq!(&tile.data[..5], [-1, 0, 0, 0, 3]);
+    assert!(!tile.data.iter().copied().any(tiles::is_void));
+}
+#[test]
+fn saturating_coord_arithmetic() {
+    let c = Coord::new(89.5, 179.5);
+    assert_eq!(c.saturating_add_to_lat(1.), Coord::new(90, 179.5));
+    assert_eq!(c.saturating_add_to_lat(-200.), Coord::new(-90, 179.5));
+    assert_eq!(c.saturating_add_to_lat(0.25), Coord::new(89.75, 179.5));
+
+    assert_eq!(c.saturating_add_to_lon(1.), Coord::new(89.5, -179.5));
+    let c = Coord::new(0, -179.5);
+    assert_eq!(c.saturating_add_to_lon(-1.), Coord::new(0, 179.5));
+    assert_eq!(c.saturating_add_to_lon(720.), c);
+    assert_eq!(Coord::wrap_longitude(180.), -180.);
+    assert_eq!(Coord::wrap_longitude(-190.), 170.);
+}
+#[test]
+fn try_new_tile() {
+    let res = Resolution::SRTM3;
+    let data = vec![0; res.total_len()];
+    assert!(Tile::try_new(44, 15, res, data.clone()).is_ok());
+    assert!(Tile::try_new(-90, -180, res, data.clone()).is_ok());
+    assert_eq!(
+        Tile::try_new(44, 15, Resolution::SRTM1, data.clone()),
+        Err(Error::filesize())
+    );
+    assert_eq!(
+        Tile::try_new(90, 15, res, data.clone()),
+        Err(Error::ParseLatLong)
+    );
+    assert_eq!(Tile::try_new(44, 180, res, data), Err(Error::ParseLatLong));
+}
+#[test]
+fn distance_between_coords() {
+    let a = Coord::new(44., 15.);
+    assert_eq!(a.distance_to(a), 0.);
+    // a degree of latitude is about 111.2 km
+    assert!((a.distance_to((45., 15.)) - 111_195.).abs() < 1.);
+    let b = Coord::new(44.4480403, 15.0733053);
+    assert_eq!(a.distance_to(b), b.distance_to(a));
+}
+#[test]
+fn grade_profile() {
+    // rising 1m every sample towards east
+    let mut tile = synthetic_tile(|_, col| col as i16);
+    tile.data[600 * tile.resolution.extent() + 605] = tiles::VOID;
+    let mut set = TileSet::new();
+    set.insert(tile);
+
+    let cell = Resolution::SRTM3.cell_size_deg();
+    let path = [Coord::new(44.5, 15.5), Coord::new(44.5, 15.5 + 10. * cell)];
+    let step = path[0].distance_to(path[1]) / 10.;
+    let profile = set.grade_profile(&path, step);
+    assert_eq!(profile.len(), 11);
+    assert_eq!(profile[0].grade, None);
+    assert_eq!(profile[0].elevation, Some(600));
+    assert_eq!(profile[10].elevation, Some(610));
+    assert!((profile[10].distance - step * 10.).abs() < 1e-6);
+
+    let expected_grade = 100. / step;
+    assert!((profile[1].grade.unwrap() - expected_grade).abs() < 1e-6);
+    // the void breaks the grade on both sides
+    assert_eq!(profile[5].elevation, None);
+    assert_eq!(profile[5].grade, None);
+    assert_eq!(profile[6].grade, None);
+    assert!(profile[7].grade.is_some());
+
+    assert!(set.grade_profile(&[], 10.).is_empty());
+    assert!(set.grade_profile(&path, 0.).is_empty());
+    assert!(set.grade_profile(&path, -1.).is_empty());
+    assert!(set.grade_profile(&path, f64::NAN).is_empty());
+    // no closer than the cells of SRTM05: 60 along the 10 of SRTM3
+    assert_eq!(set.grade_profile(&path, f64::MIN_POSITIVE).len(), 61);
+}
+#[test]
+fn owned_elevation() {
+    let coord = Coord::new(44.4480403, 15.0733053);
+    let tile = Tile::from_file("N44E015.hgt").unwrap();
+    assert_eq!(tile.elevation(coord), tile.get(coord).copied());
+    assert_eq!(tile.elevation(coord), Some(260));
+}
+#[test]
+fn tile_ids() {
+    let res = Resolution::SRTM3;
+    let a = Tile::new(44, 15, res, vec![1; res.total_len()]);
+    let b = Tile::new(44, 15, res, vec![2; res.total_len()]);
+    let c = Tile::new(44, 15, Resolution::SRTM1, vec![]);
+    assert_eq!(a.id(), b.id());
+    assert_eq!(a.id(), TileId(44, 15, res));
+    assert_ne!(a.id(), c.id());
+
+    let ids = std::collections::HashSet::from([a.id(), b.id(), c.id()]);
+    assert_eq!(ids.len(), 2);
+}
+#[test]
+fn get_clamped_near_edges() {
+    let tile = synthetic_tile(|row, col| (row * 10 + col % 7 + 1) as i16);
+    // just past the northern edge: the first row
+    assert_eq!(tile.get_clamped((45.0000001, 15.5)), Some(600 % 7 + 1));
+    // just past the south-west corner
+    assert_eq!(tile.get_clamped((43.9999999, 14.9999999)), Some(12001));
+    assert_eq!(tile.get_clamped((44.5, 15.5)), tile.elevation((44.5, 15.5)));
+    assert_eq!(tile.get_clamped((45.001, 15.5)), None);
+    assert_eq!(tile.get_clamped((44.5, 16.001)), None);
+}
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn load_dir_async() {
+    let dir = std::env::temp_dir().join("srtm_reader_load_dir_async");
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["N44E015.hgt", "N45E015.hgt"] {
+        std::fs::copy("N44E015.hgt", dir.join(name)).unwrap();
+    }
+
+    let coords = [
+        Coord::new(44.4480403, 15.0733053),
+        Coord::new(44.5, 15.5),
+        Coord::new(45.4480403, 15.0733053),
+    ];
+    let set = TileSet::load_dir_async(&dir, &coords, 1).await.unwrap();
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.elevation(coords[0]), Some(260));
+    assert_eq!(set.elevation(coords[2]), Some(260));
+
+    let missing = TileSet::load_dir_async(&dir, &[Coord::new(10.5, 10.5)], 4).await;
+    assert_eq!(
+        missing,
+        Err(Error::open(
+            dir.join("N10E010.hgt"),
+            io::ErrorKind::NotFound.into()
+        ))
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn valid_bounds() {
+    let extent = Resolution::SRTM3.extent();
+    let tile = synthetic_tile(|row, col| {
+        if (300..=600).contains(&row) && (120..=900).contains(&col) && row != col {
+            100
+        } else {
+            tiles::VOID
+        }
+    });
+    let (sw, ne) = tile.valid_bounds().unwrap();
+    assert_eq!(sw, tile.coord_at(600, 120));
+    assert_eq!(ne, tile.coord_at(300, 900));
+    assert!((sw.lat - 44.5).abs() < 1e-9 && (sw.lon - 15.1).abs() < 1e-9);
+    assert!((ne.lat - 44.75).abs() < 1e-9 && (ne.lon - 15.75).abs() < 1e-9);
+
+    assert_eq!(tile.coord_at(0, 0), Coord::new(45, 15));
+    assert_eq!(tile.coord_at(extent - 1, extent - 1), Coord::new(44, 16));
+    assert_eq!(synthetic_tile(|_, _| tiles::VOID).valid_bounds(), None);
+}
+#[cfg(feature = "rayon")]
+#[test]
+fn aspect_grid_in_pool() {
+    let tile = synthetic_tile(|row, col| (row * 3 + col * 2) as i16);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    let in_pool = tile.aspect_grid_in(&pool);
+    let global = tile.aspect_grid();
+    assert!(in_pool
+        .iter()
+        .zip(&global)
+        .all(|(a, b)| a == b || (a.is_nan() && b.is_nan())));
+    assert_eq!(tile.flow_direction_in(&pool), tile.flow_direction());
+    assert_eq!(
+        tile.multidirectional_hillshade_in(45., &pool),
+        tile.multidirectional_hillshade(45.)
+    );
+
+    let mut holes = tile.clone();
+    holes.data[100] = tiles::VOID;
+    holes.data[5000] = tiles::VOID;
+    let mut global = holes.clone();
+    assert_eq!(holes.par_fill_voids_in(1, &pool), global.par_fill_voids(1));
+    assert!(holes == global);
+}
+#[test]
+fn be_bytes_round_trip() {
+    let tile = Tile::from_file("N44E015.hgt").unwrap();
+    let bytes = tile.as_be_bytes();
+    assert_eq!(bytes, std::fs::read("N44E015.hgt").unwrap());
+
+    let mut written = Vec::new();
+    tile.write_be_bytes(&mut written).unwrap();
+    assert_eq!(written, bytes);
+
+    let data = Tile::parse_hgt(bytes.as_slice(), tile.resolution).unwrap();
+    let parsed = Tile::new(tile.latitude, tile.longitude, tile.resolution, data);
+    assert_eq!(parsed, tile);
+}
+#[test]
+fn zero_as_void() {
+    let mut tile = synthetic_tile(|row, _| row as i16);
+    tile.data[10] = tiles::VOID;
+    let sea = Coord::new(44.9999, 15.5);
+    assert_eq!(tile.elevation(sea), Some(0));
+    assert_eq!(tile.min_height(), Some(0));
+
+    let plain = tile.clone();
+    let tile = tile.with_zero_as_void(true);
+    assert!(tile.zero_is_void() && !plain.zero_is_void());
+    assert!(tile == plain);
+    assert_eq!(tile.elevation(sea), None);
+    assert_eq!(tile.min_height(), Some(1));
+    assert!(tile.treats_as_void(0) && tile.treats_as_void(tiles::VOID));
+    assert!(!tile.treats_as_void(1));
+}
+#[test]
+fn zero_as_void_interpolated() {
+    // averaging across the step from -1 to 1 gives `0`, which mustn't turn into voids
+    let step = |col: usize| if col < 600 { -1 } else { 1 };
+    let tile = synthetic_tile(|_, col| step(col)).with_zero_as_void(true);
+    let voids = |tile: &Tile| {
+        tile.data
+            .iter()
+            .filter(|e| tile.treats_as_void(**e))
+            .count()
+    };
+
+    let mut smoothed = tile.clone();
+    smoothed.smooth(SmoothKind::Box, 1);
+    assert_eq!(voids(&smoothed), 0);
+    assert_eq!(&smoothed.data[599..601], &[-1, 1]);
+
+    // along a row across the step, whole SRTM1 grids are slow in debug builds
+    let fine = Resolution::SRTM1;
+    let target = Tile::new(44, 15, fine, Vec::new());
+    for col in 1790..1810 {
+        assert!(!tile.treats_as_void(tile.upsampled_at(fine, false, 1800, col)));
+        assert!(!tile.treats_as_void(tile.regridded_at(&target, 1800, col)));
+    }
+
+    let mut gap = synthetic_tile(|_, col| if col == 600 { tiles::VOID } else { step(col) })
+        .with_zero_as_void(true);
+    assert_eq!(gap.fill_voids(1), gap.resolution.extent());
+    assert_eq!(voids(&gap), 0);
+    assert_eq!(gap.data[600], 1);
+}
+#[test]
+fn tile_summary() {
+    let mut tile = synthetic_tile(|row, col| (row + col) as i16);
+    let len = tile.data.len();
+    tile.data[..len / 4].fill(tiles::VOID);
+    tile.data[len - 1] = tiles::VOID;
+
+    assert_eq!(tile.center(), Coord::new(44.5, 15.5));
+    let summary = tile.summary();
+    assert_eq!(summary.latitude, 44);
+    assert_eq!(summary.longitude, 15);
+    assert_eq!(summary.resolution, Resolution::SRTM3);
+    assert_eq!(summary.center, tile.center());
+    assert_eq!(summary.min, tile.min_height());
+    assert_eq!(summary.max, Some(2399));
+    assert_eq!(summary.void_ratio, (len / 4 + 1) as f64 / len as f64);
+}
+#[test]
+fn chunked_parse_matches_whole_read() {
+    let bytes = std::fs::read("N44E015.hgt").unwrap();
+    let whole = bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+        .collect::<Vec<_>>();
+    let chunked = Tile::parse_hgt(bytes.as_slice(), Resolution::SRTM1).unwrap();
+    assert_eq!(chunked, whole);
+
+    let truncated = Tile::parse_hgt(&bytes[..bytes.len() - 2], Resolution::SRTM1);
+    assert!(truncated.is_err());
+}
+#[test]
+fn lookup_outcomes() {
+    let mut tile = synthetic_tile(|row, _| row as i16);
+    tile.data[600 * tile.resolution.extent() + 600] = tiles::VOID;
+    assert_eq!(tile.lookup((44.75, 15.5)), Lookup::Valid(300));
+    assert_eq!(tile.lookup((44.5, 15.5)), Lookup::Void);
+    assert_eq!(tile.lookup((45.5, 15.5)), Lookup::OutsideTile);
+    assert_eq!(tile.lookup((44.5, 14.5)), Lookup::OutsideTile);
+    assert!(tile.contains((44.5, 15.5)));
+    assert!(!tile.contains((43.5, 15.5)));
+}
+#[test]
+fn coords_from_dms() {
+    let close = |a: Coord, lat: f64, lon: f64| {
+        assert!(
+            (a.lat - lat).abs() < 1e-6 && (a.lon - lon).abs() < 1e-6,
+            "{a:?}"
+        )
+    };
+    let ne = Coord::from_dms((44, 26, 53., 'N'), (15, 4, 23.9, 'E')).unwrap();
+    close(ne, 44.448056, 15.073306);
+    let sw = Coord::from_dms((33, 52, 4.2, 'S'), (151, 12, 36., 'W')).unwrap();
+    close(sw, -33.867833, -151.21);
+    let nw = Coord::from_dms((40, 42, 46., 'n'), (74, 0, 22., 'w')).unwrap();
+    close(nw, 40.712778, -74.006111);
+    let se = Coord::from_dms((22, 54, 0., 'S'), (43, 10, 30., 'E')).unwrap();
+    close(se, -22.9, 43.175);
+
+    assert_eq!(Coord::from_dms((44, 60, 0., 'N'), (15, 0, 0., 'E')), None);
+    assert_eq!(Coord::from_dms((44, 0, 0., 'E'), (15, 0, 0., 'N')), None);
+    assert_eq!(Coord::from_dms((91, 0, 0., 'N'), (15, 0, 0., 'E')), None);
+    assert_eq!(Coord::from_dms((-44, 0, 0., 'N'), (15, 0, 0., 'E')), None);
+    assert_eq!(Coord::from_dms((44, 0, 0., 'S'), (-15, 0, 0., 'W')), None);
+
+    close(
+        Coord::parse_dms("44°26'53\"N 15°4'23.9\"E").unwrap(),
+        44.448056,
+        15.073306,
+    );
+    close(
+        Coord::parse_dms("33°52'4.2\"S, 151°12'36\"W").unwrap(),
+        -33.867833,
+        -151.21,
+    );
+    close(
+        Coord::parse_dms("22°54'S 43°10'30\"E").unwrap(),
+        -22.9,
+        43.175,
+    );
+    assert_eq!(Coord::parse_dms("44.5, 15.5"), None);
+}
+#[test]
+fn shared_edges() {
+    let res = Resolution::SRTM3;
+    let extent = res.extent();
+    // elevation depends on the global position, so neighbours agree on shared edges
+    let tile_at = |lat: i8, lon: i16| {
+        let data = (0..res.total_len())
+            .map(|i| {
+                let global_row = (45 - lat as usize) * (extent - 1) + i / extent;
+                let global_col = (lon as usize - 15) * (extent - 1) + i % extent;
+                (global_row * 3 + global_col) as i16
+            })
+            .collect();
+        Tile::new(lat, lon, res, data)
+    };
+    let (n44, n45, n44e16) = (tile_at(44, 15), tile_at(45, 15), tile_at(44, 16));
+    assert_eq!(n44.north_edge(), n45.south_edge());
+    assert_eq!(n44.east_edge(), n44e16.west_edge());
+    assert_eq!(n44.north_edge().len(), extent);
+    assert_eq!(n44.west_edge().len(), extent);
+    assert_eq!(n44.west_edge()[1], n44.data[extent]);
+    assert_ne!(n44.north_edge(), n44.south_edge());
+}
+#[test]
+fn resolution_of_file() {
+    assert_eq!(Resolution::of_file("N44E015.hgt"), Ok(Resolution::SRTM1));
+    assert_eq!(
+        Resolution::of_file("N00E000.hgt"),
+        Err(Error::open("N00E000.hgt", io::ErrorKind::NotFound.into()))
+    );
+    assert_eq!(
+        Resolution::of_file("Cargo.toml"),
+        Err(Error::filesize().in_file("Cargo.toml"))
+    );
+}
+#[test]
+fn swapped_coords() {
+    let swapped: Coord = (150., 44.).into();
+    assert!(swapped.looks_swapped());
+    assert_eq!(swapped.try_fix_swap(), Coord::new(44, 150));
+
+    let fine = Coord::new(44., 150.);
+    assert!(!fine.looks_swapped());
+    assert_eq!(fine.try_fix_swap(), fine);
+    // ambiguous, both orders are valid
+    assert!(!Coord::new(44., 15.).looks_swapped());
+    // invalid either way
+    let broken: Coord = (150., 120.).into();
+    assert!(!broken.looks_swapped());
+    assert_eq!(broken.try_fix_swap(), broken);
+}
+#[test]
+fn mean_elevation() {
+    let mut tile = synthetic_tile(|row, _| if row % 2 == 0 { 100 } else { 300 });
+    // 601 even rows, 600 odd ones
+    let expected = (601. * 100. + 600. * 300.) / 1201.;
+    assert!((tile.mean_elevation().unwrap() - expected).abs() < 1e-9);
+    // voids don't pull the mean down
+    tile.data[..1201].fill(tiles::VOID);
+    assert_eq!(tile.mean_elevation(), Some(200.));
+    assert_eq!(synthetic_tile(|_, _| tiles::VOID).mean_elevation(), None);
+}
+#[test]
+fn load_with_progress_and_cancel() {
+    let dir = std::env::temp_dir().join("srtm_reader_load_with_progress");
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["N44E015.hgt", "N44E016.hgt"] {
+        std::fs::copy("N44E015.hgt", dir.join(name)).unwrap();
+    }
+    let (sw, ne) = (Coord::new(43.5, 15.5), Coord::new(44.5, 16.5));
+
+    let mut calls = Vec::new();
+    let cancel = AtomicBool::new(false);
+    let set =
+        TileSet::load_bbox_with_progress(&dir, sw, ne, |n, all| calls.push((n, all)), &cancel);
+    let set = set.unwrap();
+    assert_eq!(set.len(), 2);
+    assert_eq!(calls, [(1, 4), (2, 4), (3, 4), (4, 4)]);
+
+    // cancel after the first tile
+    let coords = [Coord::new(44.5, 15.5), Coord::new(44.5, 16.5)];
+    let set = TileSet::load_dir_with_progress(
+        &dir,
+        &coords,
+        |_, _| cancel.store(true, Ordering::Relaxed),
+        &cancel,
+    );
+    assert_eq!(set.unwrap().len(), 1);
+
+    assert_eq!(TileSet::load_dir(&dir, &coords).unwrap().len(), 2);
+    assert_eq!(
+        TileSet::load_dir(&dir, &[Coord::new(43.5, 15.5)]),
+        Err(Error::open(
+            dir.join("N43E015.hgt"),
+            io::ErrorKind::NotFound.into()
+        ))
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn parse_from_trickling_reader() {
+    /// hands out at most a few bytes per read, like decompressors may
+    struct Trickle<'a>(&'a [u8]);
+    impl std::io::Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(7);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+    let tile = synthetic_tile(|row, col| (row * 7 + col) as i16);
+    let bytes = tile.as_be_bytes();
+    let parsed = Tile::parse_hgt(Trickle(&bytes), tile.resolution).unwrap();
+    assert_eq!(parsed, tile.data);
+}
+#[test]
+fn upsample_gradient() {
+    let fine = Resolution::SRTM1;
+    // rising 3m every SRTM3 sample, so 1m every SRTM1 sample
+    let mut tile = synthetic_tile(|_, col| (col * 3) as i16);
+    assert_eq!(tile.upsample(Resolution::SRTM3, false), None);
+    let srtm1 = Tile::new(44, 15, fine, Vec::new());
+    assert_eq!(srtm1.upsample(fine, true), None);
+
+    tile.data[600 * 1201 + 600] = tiles::VOID;
+    // a single whole grid, it's slow in debug builds, the rest is checked sample by sample
+    let up = tile.upsample(fine, false).unwrap();
+    assert_eq!(up.resolution, fine);
+    assert_eq!(up.data.len(), fine.total_len());
+    assert_eq!((up.latitude, up.longitude), (44, 15));
+    let near_void = |i: usize| (1797..=1803).contains(&i);
+    for (i, elev) in up.data.iter().enumerate().step_by(997) {
+        let (row, col) = (i / fine.extent(), i % fine.extent());
+        if !near_void(row) || !near_void(col) {
+            assert_eq!(*elev as usize, col);
+        }
+        assert_eq!(tile.upsampled_at(fine, false, row, col), *elev);
+    }
+    assert_eq!(up.elevation((44.25, 15.25)), tile.elevation((44.25, 15.25)));
+
+    let at = |row: usize, col: usize| up.data[row * fine.extent() + col];
+    assert_eq!(at(1800, 1800), tiles::VOID);
+    assert_eq!(at(1801, 1801), tiles::VOID);
+    assert_eq!(at(1800, 1803), 1803);
+    let filled = |row: usize, col: usize| tile.upsampled_at(fine, true, row, col);
+    assert_eq!(filled(1800, 1800), tiles::VOID);
+    // interpolated from the remaining samples, renormalized
+    assert_eq!(filled(1801, 1801), 1802);
+}
+#[test]
+fn error_messages() {
+    let err = Tile::from_file("/data/N44E015.hgt").unwrap_err();
+    assert_eq!(
+        err,
+        Error::open("/data/N44E015.hgt", io::ErrorKind::NotFound.into())
+    );
+    assert_eq!(err.to_string(), "tile file not found: /data/N44E015.hgt");
+}
+#[test]
+fn void_coords() {
+    let mut tile = synthetic_tile(|_, _| 100);
+    tile.data[0] = tiles::VOID;
+    tile.data[600 * 1201 + 600] = i16::MIN;
+    let voids = tile.void_coords().collect::<Vec<_>>();
+    assert_eq!(voids, [Coord::new(45, 15), Coord::new(44.5, 15.5)]);
+    assert_eq!(synthetic_tile(|_, _| 0).void_coords().count(), 0);
+}
+#[test]
+fn resolution_precision() {
+    assert!(Resolution::SRTM05.is_finer_than(Resolution::SRTM1));
+    assert!(Resolution::SRTM1.is_finer_than(Resolution::SRTM3));
+    assert!(!Resolution::SRTM3.is_finer_than(Resolution::SRTM05));
+    assert!(!Resolution::SRTM1.is_finer_than(Resolution::SRTM1));
+    for res in [Resolution::SRTM05, Resolution::SRTM1, Resolution::SRTM3] {
+        assert_eq!(res.arc_seconds() / 3600., res.cell_size_deg());
+    }
+}
+#[test]
+fn geotransform() {
+    let tile = Tile::new(44, 15, Resolution::SRTM1, vec![]);
+    let half = 1. / 7200.;
+    assert_eq!(
+        tile.geotransform(),
+        [15. - half, 1. / 3600., 0., 45. + half, 0., -1. / 3600.]
+    );
+    // the center of the pixel at `row`, `col` is the sample's coordinate
+    let [x0, dx, _, y0, _, dy] = tile.geotransform();
+    let coord = tile.coord_at(1987, 264);
+    assert!((x0 + (264. + 0.5) * dx - coord.lon).abs() < 1e-9);
+    assert!((y0 + (1987. + 0.5) * dy - coord.lat).abs() < 1e-9);
+}
+
+#[test]
+fn viewshed() {
+    // a 500m high wall running north-south, 5 cells east of the observer
+    let mut set = TileSet::new();
+    set.insert(synthetic_tile(|_, col| if col == 605 { 500 } else { 100 }));
+    let observer = Coord::new(44.5, 15.5);
+    let points = set.viewshed(observer, 2., 1000., true);
+    assert!(!points.is_empty());
+    let visible = |d_col: f64| {
+        let lon = 15.5 + d_col / 1200.;
+        points
+            .iter()
+            .find(|(c, _)| (c.lat - 44.5).abs() < 1e-9 && (c.lon - lon).abs() < 1e-9)
+            .unwrap()
+            .1
+    };
+    assert!(visible(-10.));
+    assert!(visible(3.));
+    assert!(visible(5.));
+    assert!(!visible(10.));
+    assert!(points
+        .iter()
+        .all(|(c, _)| observer.distance_to(*c) <= 1000.));
+
+    // N45 is missing
+    let points = set.viewshed((44.9975, 15.2), 2., 1000., false);
+    assert!(points.iter().any(|(c, _)| c.lat >= 45.));
+    assert!(points.iter().all(|(c, seen)| c.lat < 45. || !seen));
+    assert!(set.viewshed((45.5, 15.5), 2., 1000., true).is_empty());
+}
+
+#[test]
+fn viewshed_near_pole_and_antimeridian() {
+    let mut set = TileSet::new();
+    // cells are only millimeters wide near the pole, so only the observer's own sample is valid, to keep it quick
+    let mut polar = synthetic_tile(|row, col| {
+        if [(0, 600), (4, 600)].contains(&(row, col)) {
+            100
+        } else {
+            tiles::VOID
+        }
+    });
+    (polar.latitude, polar.longitude) = (89, 15);
+    set.insert(polar);
+    let mut eastmost = synthetic_tile(|_, _| 100);
+    (eastmost.latitude, eastmost.longitude) = (0, 179);
+    set.insert(eastmost);
+
+    for (observer, radius_m) in [
+        ((89.997, 15.5), 500.),
+        ((90., 15.5), 500.),
+        ((0.5, 179.99), 5000.),
+    ] {
+        let points = set.viewshed(observer, 2., radius_m, true);
+        assert!(!points.is_empty());
+        assert!(points
+            .iter()
+            .all(|(c, _)| Coord::opt_new(c.lat, c.lon).is_some()));
+        assert!(points.iter().any(|(_, seen)| *seen));
+    }
+}
+
+#[test]
+fn microdegrees() {
+    let coord = Coord::from_microdegrees(444_480_403, 150_733_053);
+    assert!((coord.lat - 44.4480403).abs() < 1e-12);
+    assert!((coord.lon - 15.0733053).abs() < 1e-12);
+    assert_eq!(coord.to_microdegrees(), (444_480_403, 150_733_053));
+
+    let south_west = Coord::from_microdegrees(-338_688_000, -1_799_999_999);
+    assert_eq!(south_west.to_microdegrees(), (-338_688_000, -1_799_999_999));
+    assert_eq!(Coord::new(-0.00000005, 0.).to_microdegrees(), (-1, 0));
+}
+
+#[test]
+fn col_major() {
+    let tile = synthetic_tile(|row, col| (row * 10 + col % 10) as i16);
+    let extent = tile.resolution.extent();
+    let col_major = tile.to_col_major();
+    assert_eq!(col_major.len(), tile.data.len());
+    assert_eq!(&col_major[..3], &[0, 10, 20]);
+    assert_eq!(col_major[extent], 1);
+    for (row, col) in [(0, 1), (1, 0), (7, 1200), (1200, 3), (600, 599)] {
+        assert_eq!(col_major[col * extent + row], tile.data[row * extent + col]);
+    }
+}
+
+#[test]
+fn for_coords() {
+    let tile = synthetic_tile(|row, col| {
+        if (row + col) % 7 == 0 {
+            tiles::VOID
+        } else {
+            (row + col) as i16
+        }
+    });
+    let coords: Vec<Coord> = (0..500)
+        .map(|i| Coord::new(44. + i as f64 * 0.002, 15. + i as f64 * 0.0017))
+        .collect();
+    let mut elevations = vec![None; coords.len()];
+    tile.for_coords(&coords, |i, elev| elevations[i] = elev);
+    for (coord, elev) in coords.iter().zip(&elevations) {
+        assert_eq!(*elev, tile.elevation(*coord));
+    }
+    assert!(elevations.contains(&None));
+
+    let mut outside = Vec::new();
+    tile.for_coords(&[Coord::new(45.5, 15.5)], |i, elev| outside.push((i, elev)));
+    assert_eq!(outside, vec![(0, None)]);
+}
+
+#[test]
+fn validate_tile() {
+    let mut tile = synthetic_tile(|_, _| 100);
+    assert_eq!(tile.validate(), Ok(()));
+    tile.data.pop();
+    assert_eq!(tile.validate(), Err(Error::filesize()));
+
+    let tile = Tile::new(
+        -91,
+        15,
+        Resolution::SRTM3,
+        vec![0; Resolution::SRTM3.total_len()],
+    );
+    assert_eq!(tile.validate(), Err(Error::ParseLatLong));
+}
+
+#[cfg(feature = "geoid")]
+#[test]
+fn geoid_undulation() {
+    // reference values of the full EGM96 model
+    for (lat, lon, expected) in [
+        (0., 0., 17.16),
+        (44.4480403, 15.0733053, 44.14),
+        (38.628155, -90.220845, -31.63),
+        (-14.621217, -54.978886, -2.97),
+        (46.874, 102.448, -43.57),
+        (90., 0., 13.6),
+        (-90., 0., -29.5),
+    ] {
+        let coord = Coord::new(lat, lon);
+        let undulation = coord.geoid_undulation();
+        assert!(
+            (undulation - expected).abs() < 3.,
+            "{coord:?}: {undulation}"
+        );
+    }
+    // wraps around the antimeridian
+    let east = Coord::new(10., 179.999).geoid_undulation();
+    let west = Coord::new(10., -180.).geoid_undulation();
+    assert!((east - west).abs() < 0.01);
+
+    let coord = Coord::new(44.4480403, 15.0733053);
+    let gps = coord.ellipsoidal_height(260.);
+    assert!((gps - 260. - coord.geoid_undulation()).abs() < 1e-9);
+    assert!((coord.orthometric_height(gps) - 260.).abs() < 1e-9);
+}
+
+#[test]
+fn from_file_with_resolution() {
+    let dir = std::env::temp_dir().join("srtm_reader_from_file_with_resolution");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("N44E015.hgt");
+    let tile = synthetic_tile(|row, col| (row + col) as i16);
+    std::fs::write(&path, tile.as_be_bytes()).unwrap();
+
+    assert_eq!(
+        Tile::from_file_with_resolution(&path, Resolution::SRTM3),
+        Ok(tile)
+    );
+    assert_eq!(
+        Tile::from_file_with_resolution(&path, Resolution::SRTM1),
+        Err(Error::filesize().in_file(&path))
+    );
+    assert_eq!(
+        Tile::from_file_with_resolution("N44E015.hgt", Resolution::SRTM3),
+        Err(Error::filesize().in_file("N44E015.hgt"))
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn default_tile() {
+    let tile = Tile::default();
+    assert_eq!(tile.validate(), Ok(()));
+    assert!(tile.is_all_void());
+    assert_eq!(tile.get((0.5, 0.5)), None);
+    assert_eq!(tile.lookup((0.5, 0.5)), Lookup::Void);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn write_png_colored() {
+    let mut tile = synthetic_tile(|row, _| row as i16);
+    let extent = tile.resolution.extent();
+    tile.data[5 * extent + 5] = tiles::VOID;
+    let path = std::env::temp_dir().join("srtm_reader_write_png_colored.png");
+    tile.write_png_colored(&path, ColorRamp::Terrain).unwrap();
+
+    let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.width, info.height), (extent as u32, extent as u32));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+
+    let pixel = |pixels: &[u8], row: usize, col: usize| {
+        let i = (row * extent + col) * 4;
+        [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+    };
+    assert_eq!(pixel(&pixels, 0, 0), [0, 97, 71, 255]);
+    assert_eq!(pixel(&pixels, extent - 1, 0), [255, 255, 255, 255]);
+    assert_eq!(pixel(&pixels, 5, 5)[3], 0);
+    assert_eq!(ColorRamp::Viridis.color(0.), [68, 1, 84]);
+    assert_eq!(ColorRamp::Viridis.color(2.), [253, 231, 37]);
+    assert_eq!(ColorRamp::Grayscale.color(0.5), [128, 128, 128]);
+
+    // a spike wider than `i16` can span
+    tile.data[0] = -32767;
+    tile.data[1] = 30000;
+    tile.write_png_colored(&path, ColorRamp::Grayscale).unwrap();
+    let mut reader = png::Decoder::new(std::fs::File::open(&path).unwrap())
+        .read_info()
+        .unwrap();
+    reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(pixel(&pixels, 0, 0), [0, 0, 0, 255]);
+    assert_eq!(pixel(&pixels, 0, 1), [255, 255, 255, 255]);
+    std::fs::remove_file(&path).unwrap();
+
+    let missing_dir = std::env::temp_dir().join("srtm_reader_no_such_dir/tile.png");
+    let err = tile
+        .write_png_colored(&missing_dir, ColorRamp::Terrain)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::write(io::ErrorKind::NotFound.into()).in_file(&missing_dir)
+    );
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn elevation_range() {
+    let tile = Tile::from_file("N44E015.hgt").unwrap();
+    let (min, max) = tile.elevation_range().unwrap();
+    assert_eq!(Some(min), tile.min_height());
+    assert_eq!(Some(max), tile.max_height());
+
+    let mut tile = synthetic_tile(|row, col| (row as i16 - col as i16) / 2);
+    tile.data[0] = tiles::VOID;
+    assert_eq!(tile.elevation_range(), Some((-600, 600)));
+    assert_eq!(synthetic_tile(|_, _| tiles::VOID).elevation_range(), None);
+}
+
+#[test]
+fn shared_tile_set() {
+    let set = SharedTileSet::new(".");
+    let first = set.get((44.4480403, 15.0733053)).unwrap();
+    let second = set.get((44.9, 15.9)).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+    assert_eq!(set.len(), 1);
+    assert_eq!(set.elevation((44.4480403, 15.0733053)), Ok(Some(260)));
+
+    let from_thread =
+        std::thread::scope(|s| s.spawn(|| set.get((44.1, 15.1)).unwrap()).join()).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &from_thread));
+
+    assert!(matches!(set.get((45.5, 15.5)), Err(Error::NotFound { .. })));
+    drop((second, from_thread));
+    assert_eq!(set.evict_unused(), 0);
+    drop(first);
+    assert_eq!(set.evict_unused(), 1);
+    assert!(set.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn shared_tile_set_serves_cached_tiles_while_loading() {
+    let dir = std::env::temp_dir().join("srtm_reader_shared_tile_set_loading");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy("N44E015.hgt", dir.join("N44E015.hgt")).unwrap();
+    // opening a fifo blocks until someone opens it for writing, so the load stays in progress until then
+    let fifo = dir.join("N45E015.hgt");
+    assert!(std::process::Command::new("mkfifo")
+        .arg(&fifo)
+        .status()
+        .unwrap()
+        .success());
+
+    let set = SharedTileSet::new(&dir);
+    let cached = set.get((44.5, 15.5)).unwrap();
+    std::thread::scope(|s| {
+        let loading = s.spawn(|| set.get((45.5, 15.5)));
+        // returns once the loading thread opened the fifo
+        let writer = std::fs::OpenOptions::new().write(true).open(&fifo).unwrap();
+        let again = set.get((44.5, 15.5)).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&cached, &again));
+        assert_eq!(set.elevation((44.4480403, 15.0733053)), Ok(Some(260)));
+        drop(writer);
+        assert!(loading.join().unwrap().is_err());
+    });
+    assert_eq!(set.len(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn has_valid_data_at() {
+    let mut tile = synthetic_tile(|_, _| 100);
+    tile.data[600 * tile.resolution.extent() + 600] = tiles::VOID;
+    assert!(tile.contains((44.5, 15.5)));
+    assert!(!tile.has_valid_data_at((44.5, 15.5)));
+    assert!(tile.has_valid_data_at((44.25, 15.5)));
+    assert!(!tile.has_valid_data_at((45.5, 15.5)));
+}
+
+#[test]
+fn write_csv() {
+    let mut tile = synthetic_tile(|_, _| tiles::VOID);
+    let extent = tile.resolution.extent();
+    tile.data[0] = 12;
+    tile.data[600 * extent + 1] = -3;
+
+    let mut csv = Vec::new();
+    tile.write_csv(&mut csv, true).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "lat,lon,elevation\n45.0000000,15.0000000,12\n44.5000000,15.0008333,-3\n"
+    );
+
+    let mut csv = Vec::new();
+    tile.write_csv(&mut csv, false).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), tile.data.len() + 1);
+    assert_eq!(csv.lines().nth(2), Some("45.0000000,15.0008333,-9999"));
+}
+
+#[test]
+fn round_coord() {
+    let coord = Coord::new(44.4480403, 15.0733553);
+    assert_eq!(coord.round_to(3), Coord::new(44.448, 15.073));
+    assert_eq!(coord.round_to(5), Coord::new(44.44804, 15.07336));
+    assert_eq!(coord.round_to(0), Coord::new(44., 15.));
+
+    let south_west = Coord::new(-33.8688197, -151.2092955);
+    assert_eq!(south_west.round_to(3), Coord::new(-33.869, -151.209));
+    assert_eq!(south_west.round_to(5), Coord::new(-33.86882, -151.2093));
+    assert_eq!(
+        Coord::new(-0.0005, 0.0015).round_to(3),
+        Coord::new(-0.001, 0.002)
+    );
+}
+
+#[test]
+fn validate_file() {
+    assert_eq!(
+        super::validate_file("N44E015.hgt", false),
+        Ok(Resolution::SRTM1)
+    );
+    assert_eq!(
+        super::validate_file("N44E015.hgt", true),
+        Ok(Resolution::SRTM1)
+    );
+
+    let dir = std::env::temp_dir().join("srtm_reader_validate_file");
+    std::fs::create_dir_all(&dir).unwrap();
+    let bytes = synthetic_tile(|_, _| 100).as_be_bytes();
+    let truncated = dir.join("N44E015.hgt");
+    std::fs::write(&truncated, &bytes[..bytes.len() - 2]).unwrap();
+    assert_eq!(
+        super::validate_file(&truncated, true),
+        Err(Error::filesize().in_file(&truncated))
+    );
+    let misnamed = dir.join("elevations.hgt");
+    std::fs::write(&misnamed, &bytes).unwrap();
+    assert_eq!(
+        super::validate_file(&misnamed, true),
+        Err(Error::FilenameHemisphere)
+    );
+    let missing = dir.join("N45E015.hgt");
+    assert_eq!(
+        super::validate_file(&missing, false),
+        Err(Error::open(missing, io::ErrorKind::NotFound.into()))
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn highest_in_sector() {
+    let mut set = TileSet::new();
+    let extent = Resolution::SRTM3.extent();
+    let mut south = synthetic_tile(|_, _| 100);
+    south.data[100 * extent + 600] = 800;
+    let mut north = synthetic_tile(|_, _| 100);
+    north.latitude = 45;
+    north.data[1190 * extent + 600] = 900;
+    set.insert(south);
+    set.insert(north);
+
+    let from = Coord::new(44.95, 15.5);
+    let (peak, elev) = set.highest_in_sector(from, 0., 30., 10_000.).unwrap();
+    assert_eq!(elev, 900);
+    assert!(peak.distance_to(Coord::new(45. + 10. / 1200., 15.5)) < 1.);
+    let (peak, elev) = set.highest_in_sector(from, 180., 30., 10_000.).unwrap();
+    assert_eq!(elev, 800);
+    assert!(peak.distance_to(Coord::new(45. - 100. / 1200., 15.5)) < 1.);
+    // the peaks are out of the sector, or too far
+    assert_eq!(
+        set.highest_in_sector(from, 90., 10., 10_000.).unwrap().1,
+        100
+    );
+    assert_eq!(set.highest_in_sector(from, 0., 30., 6_000.).unwrap().1, 100);
+    assert_eq!(set.highest_in_sector(from, 0., 30., 10.), None);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_rows() {
+    use rayon::prelude::*;
+
+    let tile = synthetic_tile(|row, col| (row + col) as i16);
+    let parallel: i64 = tile
+        .par_rows()
+        .map(|(_, row)| row.iter().map(|elev| *elev as i64).sum::<i64>())
+        .sum();
+    let serial: i64 = tile.data.iter().map(|elev| *elev as i64).sum();
+    assert_eq!(parallel, serial);
+
+    let rows: Vec<_> = tile.par_rows().collect();
+    assert_eq!(rows.len(), tile.resolution.extent());
+    assert_eq!(rows[0].0, Coord::new(45, 15));
+    assert_eq!(rows[1200].0, Coord::new(44, 15));
+    assert_eq!(rows[3].1[5], 8);
+}
+
+#[test]
+fn clamp_elevations() {
+    let mut tile = synthetic_tile(|_, _| 300);
+    tile.data[10] = 30_000;
+    tile.data[11] = -2_000;
+    tile.data[12] = tiles::VOID;
+    tile.clamp_elevations(-100, 2_000);
+    assert_eq!(&tile.data[9..14], &[300, 2_000, -100, tiles::VOID, 300]);
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn from_url() {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    let tile = synthetic_tile(|row, col| (row + col) as i16);
+    let body = tile.as_be_bytes();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = String::new();
+            BufReader::new(&stream).read_line(&mut request).unwrap();
+            let path = request.split([' ', '?']).nth(1).unwrap_or_default();
+            let response = match path {
+                "/tiles/N44E015.hgt" => {
+                    let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+                    [head.as_bytes(), &body].concat()
+                }
+                "/old/N44E015.hgt" => {
+                    b"HTTP/1.1 301 Moved Permanently\r\nLocation: /tiles/N44E015.hgt\r\nContent-Length: 0\r\n\r\n".to_vec()
+                }
+                _ => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_vec(),
+            };
+            stream.write_all(&response).unwrap();
+        }
+    });
+
+    let url = |path: &str| format!("http://{addr}{path}");
+    assert!(Tile::from_url(&url("/tiles/N44E015.hgt")) == Ok(tile.clone()));
+    assert!(Tile::from_url(&url("/old/N44E015.hgt?key=1")) == Ok(tile));
+    assert_eq!(
+        Tile::from_url(&url("/tiles/N45E015.hgt")),
+        Err(Error::Http(Some(404)))
+    );
+    assert_eq!(Tile::from_url(&url("/tiles/")), Err(Error::FilenameLength));
+}
+
+#[cfg(all(feature = "http", feature = "gzip"))]
+#[test]
+fn from_url_gzipped() {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    let tile = synthetic_tile(|row, col| (row * 3 + col) as i16);
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    gz.write_all(&tile.as_be_bytes()).unwrap();
+    let body = gz.finish().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            BufReader::new(&stream)
+                .read_line(&mut String::new())
+                .unwrap();
+            let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+            stream
+                .write_all(&[head.as_bytes(), &body].concat())
+                .unwrap();
+        }
+    });
+
+    let tile_from_url = Tile::from_url(&format!("http://{addr}/tiles/N44E015.hgt.gz")).unwrap();
+    assert!(tile_from_url == tile);
+}
+
+#[test]
+fn tile_difference() {
+    let mut tile = synthetic_tile(|row, col| (row + col) as i16);
+    tile.data[7] = tiles::VOID;
+    let difference = tile.difference(&tile).unwrap();
+    assert_eq!(difference.len(), tile.data.len());
+    assert_eq!(difference[7], tiles::VOID);
+    assert!(difference
+        .iter()
+        .enumerate()
+        .all(|(i, diff)| i == 7 || *diff == 0));
+
+    let raised = tile.mapped(|elev| elev + 5);
+    assert!(raised.difference(&tile).unwrap()[8..]
+        .iter()
+        .all(|diff| *diff == 5));
+
+    let mut elsewhere = tile.clone();
+    elsewhere.latitude = 45;
+    assert_eq!(tile.difference(&elsewhere), Err(Error::TileMismatch));
+    assert_eq!(
+        tile.difference(&Tile::from_file("N44E015.hgt").unwrap()),
+        Err(Error::TileMismatch)
+    );
+}
+
+#[test]
+fn tile_paths() {
+    use coords::TileLayout;
+
+    let veli_brig = Coord::new(44.4480403, 15.0733053);
+    assert_eq!(
+        veli_brig.tile_path(TileLayout::Flat),
+        PathBuf::from("N44E015.hgt")
+    );
+    for (coord, continent) in [
+        (veli_brig, "Eurasia"),
+        (Coord::new(-3.5, 37.35), "Africa"),
+        (Coord::new(39.1, -105.5), "North_America"),
+        (Coord::new(-32.65, -70.01), "South_America"),
+        (Coord::new(-25.34, 131.03), "Australia"),
+        (Coord::new(27.98, 86.92), "Eurasia"),
+    ] {
+        assert_eq!(
+            coord.tile_path(TileLayout::ContinentSubdir),
+            Path::new(continent).join(coord.get_filename())
+        );
+    }
+
+    let by_latitude =
+        |coord: Coord| PathBuf::from(format!("lat{}", coord.trunc().0)).join(coord.get_filename());
+    assert_eq!(
+        veli_brig.tile_path(TileLayout::Custom(&by_latitude)),
+        PathBuf::from("lat44/N44E015.hgt")
+    );
+}
+
+#[test]
+fn elevation_grid() {
+    // elevations grow by one with every sample eastwards, over both tiles
+    let west = synthetic_tile(|_, col| col as i16);
+    let mut east = synthetic_tile(|_, col| 1200 + col as i16);
+    east.longitude = 16;
+    let mut set = TileSet::new();
+    set.insert(west);
+    set.insert(east);
+
+    let (grid, rows, cols) = set.elevation_grid((44.4, 15.9), (44.5, 16.1), Resolution::SRTM3);
+    assert_eq!((rows, cols), (121, 241));
+    assert_eq!(grid.len(), rows * cols);
+    for row in grid.chunks(cols) {
+        assert_eq!(row[0], Some(1080));
+        assert!(row
+            .windows(2)
+            .all(|pair| pair[1] == pair[0].map(|elev| elev + 1)));
+    }
+
+    let (grid, rows, cols) = set.elevation_grid((44.9, 15.5), (45.1, 15.6), Resolution::SRTM3);
+    assert_eq!(grid[0], None);
+    assert_eq!(grid[(rows - 1) * cols], Some(600));
+}
+
+#[test]
+fn normalize_coord() {
+    assert_eq!(Coord::normalize(44.5, 15.5), Some(Coord::new(44.5, 15.5)));
+    assert_eq!(Coord::normalize(44.5, -190.), Some(Coord::new(44.5, 170.)));
+    assert_eq!(Coord::normalize(44.5, 180.), Some(Coord::new(44.5, -180.)));
+    assert_eq!(Coord::normalize(44.5, 540.), Some(Coord::new(44.5, -180.)));
+    assert_eq!(Coord::normalize(91., 15.), Some(Coord::new(90., 15.)));
+    assert_eq!(Coord::normalize(-100., -15.), Some(Coord::new(-90., -15.)));
+    assert_eq!(Coord::normalize(f64::NAN, 15.), None);
+    assert_eq!(Coord::normalize(44., f64::NAN), None);
+    assert_eq!(Coord::normalize(f64::INFINITY, 15.), None);
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn from_zip_file() {
+    use std::io::Write;
+
+    let dir = std::env::temp_dir().join("srtm_reader_from_zip_file");
+    std::fs::create_dir_all(&dir).unwrap();
+    let tile = synthetic_tile(|row, col| (row + col) as i16);
+    let zip_with = |name: &str, entries: &[(&str, &[u8])]| {
+        let path = dir.join(name);
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        for (entry, bytes) in entries {
+            zip.start_file(*entry, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    };
+
+    let bytes = tile.as_be_bytes();
+    let path = zip_with(
+        "N44E015.SRTMGL3.hgt.zip",
+        &[("README.txt", b"srtm"), ("N44E015.hgt", &bytes)],
+    );
+    assert!(Tile::from_zip_file(&path) == Ok(tile.clone()));
+    // recognized by its content by `from_file` too
+    let renamed = dir.join("N44E015.hgt");
+    std::fs::rename(&path, &renamed).unwrap();
+    assert!(Tile::from_file(&renamed) == Ok(tile));
+
+    let path = zip_with("N44E015.SRTMGL3.hgt.zip", &[("README.txt", b"srtm")]);
+    assert_eq!(Tile::from_zip_file(&path), Err(Error::Archive));
+    assert_eq!(Tile::from_zip_file("N44E015.hgt"), Err(Error::Archive));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn apply_offsets() {
+    let mut tile = synthetic_tile(|_, _| 100);
+    tile.data[0] = i16::MAX - 10;
+    tile.data[1] = tiles::VOID;
+    tile.apply_offset(20);
+    assert_eq!(&tile.data[..3], &[i16::MAX, tiles::VOID, 120]);
+    tile.apply_offset(i16::MIN);
+    assert_eq!(&tile.data[..3], &[-1, tiles::VOID, -32_648]);
+    // clamped short of `i16::MIN`, which is a void
+    tile.apply_offset(-1_000);
+    assert_eq!(tile.data[2], i16::MIN + 1);
+    assert!(!tile.treats_as_void(tile.data[2]));
+
+    // landing on the void marker stops a meter short, from either side
+    let mut tile = synthetic_tile(|_, _| 1);
+    tile.data[0] = -19_999;
+    tile.apply_offset(-10_000);
+    assert_eq!(&tile.data[..2], &[-29_999, -9_998]);
+    tile.data[0] = -19_999;
+    tile.apply_offset(10_000);
+    assert_eq!(&tile.data[..2], &[-10_000, 2]);
+    let mut coastal = synthetic_tile(|_, _| 5).with_zero_as_void(true);
+    coastal.apply_offset(-5);
+    assert_eq!(coastal.data[0], 1);
+
+    let mut tile = synthetic_tile(|_, _| 100);
+    tile.data[1] = tiles::VOID;
+    tile.data[3] = -9_990;
+    let grid: Vec<i16> = (0..tile.data.len())
+        .map(|i| if i == 3 { -9 } else { (i % 3) as i16 })
+        .collect();
+    tile.apply_correction_grid(&grid);
+    assert_eq!(&tile.data[..5], &[100, tiles::VOID, 102, -9_998, 101]);
+}
+
+#[test]
+fn tile_exists() {
+    let dir = std::env::temp_dir().join("srtm_reader_tile_exists");
+    std::fs::create_dir_all(&dir).unwrap();
+    assert!(!super::tile_exists((44.5, 15.5), &dir));
+    std::fs::write(dir.join("N44E015.hgt"), []).unwrap();
+    std::fs::write(dir.join("N45E015.hgt.zip"), []).unwrap();
+    std::fs::create_dir_all(dir.join("N46E015.hgt")).unwrap();
+    assert!(super::tile_exists((44.5, 15.5), &dir));
+    assert!(super::tile_exists((45.5, 15.5), &dir));
+    assert!(!super::tile_exists((46.5, 15.5), &dir));
+    assert!(!super::tile_exists((44.5, 16.5), &dir));
+    assert!(super::tile_exists((44.5, 15.5), "."));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sample_line() {
+    let mut tile = synthetic_tile(|row, col| (row + col) as i16);
+    let extent = tile.resolution.extent();
+    tile.data[10 * extent + 10] = tiles::VOID;
+    // from the north-west towards the south-east corner, elevations grow
+    let line = tile.sample_line(Coord::new(44.999, 15.001), Coord::new(44.9, 15.05));
+    assert_eq!(line.first().unwrap().0, tile.coord_at(1, 1));
+    assert_eq!(line.last().unwrap().0, tile.coord_at(120, 60));
+    assert_eq!(line.len(), 120);
+    let valid: Vec<i16> = line.iter().filter_map(|(_, elev)| *elev).collect();
+    assert!(valid.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let diagonal = tile.sample_line(Coord::new(44.999, 15.001), Coord::new(44.98, 15.02));
+    assert_eq!(diagonal.len(), 24);
+    assert_eq!(diagonal[9], (tile.coord_at(10, 10), None));
+    assert!(diagonal
+        .iter()
+        .enumerate()
+        .all(|(i, (_, elev))| i == 9 || *elev == Some(2 * (i as i16 + 1))));
+
+    let point = tile.sample_line(Coord::new(44.5, 15.5), Coord::new(44.5, 15.5));
+    assert_eq!(point, vec![(tile.coord_at(600, 600), Some(1200))]);
+    assert!(tile
+        .sample_line(Coord::new(44.5, 15.5), Coord::new(45.5, 15.5))
+        .is_empty());
+}
+
+#[test]
+fn resolution_from_extent() {
+    assert_eq!(Resolution::try_from_extent(7201), Some(Resolution::SRTM05));
+    assert_eq!(Resolution::try_from_extent(3601), Some(Resolution::SRTM1));
+    assert_eq!(Resolution::try_from_extent(1201), Some(Resolution::SRTM3));
+    assert_eq!(Resolution::try_from_extent(3600), None);
+    assert_eq!(Resolution::try_from_extent(0), None);
+}
+
+#[test]
+fn fill_voids() {
+    let mut tile = synthetic_tile(|row, col| (row + col) as i16);
+    let extent = tile.resolution.extent();
+    tile.data[600 * extent + 600] = tiles::VOID;
+    tile.fill_voids(1);
+    assert_eq!(tile.data[600 * extent + 600], 1200);
+
+    // a hole too large for the radius is only filled around its rim
+    let mut tile = synthetic_tile(|row, col| {
+        if (10..20).contains(&row) && (10..20).contains(&col) {
+            tiles::VOID
+        } else {
+            100
+        }
+    });
+    assert_eq!(tile.fill_voids(2), 100 - 36);
+    assert_eq!(tile.data[10 * extent + 10], 100);
+    assert_eq!(tile.data[15 * extent + 15], tiles::VOID);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_fill_voids_matches_serial() {
+    let mut serial = synthetic_tile(|row, col| {
+        if (row * 7 + col * 13) % 11 == 0 || (300..320).contains(&row) {
+            tiles::VOID
+        } else {
+            (row * 2 + col) as i16
+        }
+    });
+    let mut parallel = serial.clone();
+    let filled = serial.fill_voids(3);
+    assert!(filled > 0);
+    assert_eq!(parallel.par_fill_voids(3), filled);
+    assert!(serial == parallel);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn metadata_json() {
+    let tile = Tile::from_file("N44E015.hgt").unwrap();
+    let json: serde_json::Value = serde_json::from_str(&tile.metadata_json()).unwrap();
+    assert_eq!(json["lat"], 44);
+    assert_eq!(json["lon"], 15);
+    assert_eq!(json["resolution"], "SRTM1");
+    assert_eq!(json["extent"], 3601);
+    assert_eq!(json["min"], tile.min_height().unwrap());
+    assert_eq!(json["max"], tile.max_height().unwrap());
+    assert_eq!(json["void_ratio"], tile.void_ratio());
+    assert_eq!(json["center"]["lat"], 44.5);
+    assert_eq!(json["center"]["lon"], 15.5);
+    assert!(json.get("data").is_none());
+
+    let json: serde_json::Value =
+        serde_json::from_str(&synthetic_tile(|_, _| tiles::VOID).metadata_json()).unwrap();
+    assert!(json["min"].is_null());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let tile = Tile::from_file("N44E015.hgt")
+        .unwrap()
+        .with_zero_as_void(true);
+    let bytes = bincode::serialize(&tile).unwrap();
+    // the elevations as raw bytes, plus a small header
+    assert!(bytes.len() < tile.data.len() * 2 + 64);
+    let back: Tile = bincode::deserialize(&bytes).unwrap();
+    assert!(back == tile);
+    // a way of reading the data, not part of it
+    assert!(!back.zero_is_void());
+
+    let coord = Coord::new(44.5, 15.25);
+    let json = serde_json::to_string(&coord).unwrap();
+    assert_eq!(serde_json::from_str::<Coord>(&json).unwrap(), coord);
+    let json = serde_json::to_string(&Resolution::SRTM3).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Resolution>(&json).unwrap(),
+        Resolution::SRTM3
+    );
+
+    let tile = synthetic_tile(|row, col| (row * 3 + col) as i16);
+    let json = serde_json::to_string(&tile).unwrap();
+    assert!(serde_json::from_str::<Tile>(&json).unwrap() == tile);
+    assert!(bincode::deserialize::<Tile>(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn interpolated_with_neighbors() {
+    // elevations grow by one with every sample eastwards, over both tiles
+    let west = synthetic_tile(|_, col| col as i16);
+    let mut east = synthetic_tile(|_, col| 1200 + col as i16);
+    east.longitude = 16;
+
+    let at = |lon: f64| {
+        west.get_interpolated_with_neighbors((44.5, lon), &[&east])
+            .unwrap()
+    };
+    assert!((at(15.5) - 600.).abs() < 1e-9);
+    assert!((at(15.5 + 0.25 / 1200.) - 600.25).abs() < 1e-9);
+    // approaching the seam from the west, then past it, in the eastern tile
+    let below = at(16. - 0.5 / 1200.);
+    let on = at(16.);
+    let above = at(16. + 0.5 / 1200.);
+    assert!((below - 1199.5).abs() < 1e-6, "{below}");
+    assert!((on - 1200.).abs() < 1e-6, "{on}");
+    assert!((above - 1200.5).abs() < 1e-6, "{above}");
+
+    assert_eq!(
+        west.get_interpolated_with_neighbors((44.5, 16.5), &[]),
+        None
+    );
+    assert_eq!(
+        east.get_interpolated_with_neighbors((44.5, 15.5), &[&west]),
+        Some(600.)
+    );
+    // on the seam, without the tile that contains it, from the edge of the neighbour
+    assert_eq!(
+        west.get_interpolated_with_neighbors((44.5, 16.), &[]),
+        Some(1200.)
+    );
+    east.data[600 * east.resolution.extent() + 1] = tiles::VOID;
+    assert_eq!(
+        west.get_interpolated_with_neighbors((44.5, 16. + 0.5 / 1200.), &[&east]),
+        None
+    );
+}
+
+#[test]
+fn from_bytes_named() {
+    static EMBEDDED: &[u8] = include_bytes!("../N44E015.hgt");
+    let tile = Tile::from_bytes_named(EMBEDDED, "N44E015.hgt").unwrap();
+    assert!(tile == Tile::from_file("N44E015.hgt").unwrap());
+    assert_eq!(tile.elevation((44.4480403, 15.0733053)), Some(260));
+
+    assert_eq!(
+        Tile::from_bytes_named(EMBEDDED, "elevations"),
+        Err(Error::FilenameHemisphere)
+    );
+    assert_eq!(
+        Tile::from_bytes_named(&EMBEDDED[..100], "N44E015"),
+        Err(Error::filesize())
+    );
+}
+
+#[test]
+fn from_bytes() {
+    let bytes = std::fs::read("N44E015.hgt").unwrap();
+    let tile = Tile::from_bytes(&bytes, 44, 15).unwrap();
+    assert!(tile == Tile::from_file("N44E015.hgt").unwrap());
+
+    let tile = synthetic_tile(|row, col| (row * 7 + col) as i16);
+    let moved = Tile::from_bytes(&tile.as_be_bytes(), -12, -170).unwrap();
+    assert_eq!((moved.latitude, moved.longitude), (-12, -170));
+    assert_eq!(moved.resolution, Resolution::SRTM3);
+    assert_eq!(moved.data, tile.data);
+
+    assert_eq!(
+        Tile::from_bytes(&bytes[..bytes.len() - 2], 44, 15),
+        Err(Error::filesize())
+    );
+    assert_eq!(Tile::from_bytes(&bytes, 90, 15), Err(Error::ParseLatLong));
+}
+
+#[test]
+fn convolve3x3() {
+    let mut tile = synthetic_tile(|row, col| (row * 3 + col) as i16);
+    let extent = tile.resolution.extent();
+    tile.data[600 * extent + 600] = tiles::VOID;
+
+    let identity = tile.convolve3x3(&[[0., 0., 0.], [0., 1., 0.], [0., 0., 0.]]);
+    assert_eq!(identity.len(), tile.data.len());
+    assert_eq!(identity[0], None);
+    assert_eq!(identity[extent + 1], Some(4.));
+    assert_eq!(identity[100 * extent + 7], Some(307.));
+
+    // on a plane, the box blur is the center value, but voids in the window spoil it
+    let boxed = tile.convolve3x3(&[[1. / 9.; 3]; 3]);
+    assert!((boxed[100 * extent + 7].unwrap() - 307.).abs() < 1e-9);
+    assert_eq!(boxed[601 * extent + 599], None);
+    assert_eq!(boxed[(extent - 1) * extent + 7], None);
+
+    let east_west = tile.convolve3x3(&[[0., 0., 0.], [-1., 0., 1.], [0., 0., 0.]]);
+    assert_eq!(east_west[100 * extent + 7], Some(2.));
+}
+
+#[test]
+fn smooth() {
+    // deterministic noise around 1000m
+    let noisy = synthetic_tile(|row, col| {
+        let hash = (row * 7919 + col * 104_729) % 101;
+        1000 + hash as i16 - 50
+    });
+    let variance = |tile: &Tile| {
+        let mean = tile.mean_elevation().unwrap();
+        let valid: Vec<f64> = tile
+            .data
+            .iter()
+            .filter(|elev| !tile.treats_as_void(**elev))
+            .map(|elev| (*elev as f64 - mean).powi(2))
+            .collect();
+        valid.iter().sum::<f64>() / valid.len() as f64
+    };
+    for kind in [SmoothKind::Box, SmoothKind::Gaussian] {
+        let mut tile = noisy.clone();
+        tile.data[7] = tiles::VOID;
+        tile.smooth(kind, 2);
+        assert!(variance(&tile) < variance(&noisy) / 4., "{kind:?}");
+        assert_eq!(tile.data[7], tiles::VOID);
+        assert!(tile
+            .data
+            .iter()
+            .all(|elev| *elev == tiles::VOID || (950..=1050).contains(elev)));
+    }
+
+    let mut flat = synthetic_tile(|_, _| 300);
+    flat.data[5] = tiles::VOID;
+    let mut smoothed = flat.clone();
+    smoothed.smooth(SmoothKind::Gaussian, 3);
+    assert!(smoothed == flat);
+}
+
+#[test]
+fn pixel_bounds() {
+    let tile = synthetic_tile(|_, _| 0);
+    // reaching past the north-west corner: clamped to the first row and column
+    assert_eq!(
+        tile.pixel_bounds(Coord::new(44.5, 14.5), Coord::new(45.5, 15.)),
+        Some((0, 0, 600, 0))
+    );
+    assert_eq!(
+        tile.pixel_bounds(Coord::new(44.5, 15.25), Coord::new(45.5, 15.5)),
+        Some((0, 300, 600, 600))
+    );
+    // reaching past the south-east corner
+    assert_eq!(
+        tile.pixel_bounds(Coord::new(43.2, 15.75), Coord::new(44.25, 16.8)),
+        Some((900, 900, 1200, 1200))
+    );
+    // between two samples: the nearest one
+    let step = 1. / 1200.;
+    assert_eq!(
+        tile.pixel_bounds(
+            Coord::new(44.5 + step / 4., 15.5 + step / 4.),
+            Coord::new(44.5 + step / 2., 15.5 + step / 2.)
+        ),
+        Some((600, 600, 600, 600))
+    );
+    assert_eq!(
+        tile.pixel_bounds(
+            Coord::new(44.5 + step * 0.6, 15.5 + step / 4.),
+            Coord::new(44.5 + step * 0.9, 15.5 + step * 3.1)
+        ),
+        Some((599, 601, 599, 603))
+    );
+    // upside down
+    assert_eq!(
+        tile.pixel_bounds(Coord::new(44.6, 15.5), Coord::new(44.5, 15.6)),
+        None
+    );
+    assert_eq!(
+        tile.pixel_bounds(Coord::new(46., 15.), Coord::new(47., 16.)),
+        None
+    );
+    assert_eq!(
+        tile.pixel_bounds(Coord::new(44., 13.), Coord::new(45., 14.9)),
+        None
+    );
+}
+
+#[test]
+fn collect_tile_set() {
+    let tile_at =
+        |lat, lon, res: Resolution, elev| Tile::new(lat, lon, res, vec![elev; res.total_len()]);
+    let set: TileSet = vec![
+        tile_at(44, 15, Resolution::SRTM1, 1),
+        tile_at(45, 15, Resolution::SRTM3, 2),
+        tile_at(44, 15, Resolution::SRTM3, 3),
+        tile_at(45, 15, Resolution::SRTM3, 4),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.elevation((44.5, 15.5)), Some(1));
+    assert_eq!(set.elevation((45.5, 15.5)), Some(4));
+    assert_eq!(set.elevation((46.5, 15.5)), None);
+}
+
+#[test]
+fn simplify_with_elevation() {
+    // due north, about 11m between points, all at 100m but a 30m spike in the middle
+    let track: Vec<(Coord, Option<i16>)> = (0..=100)
+        .map(|i| {
+            let elev = if i == 50 { 130 } else { 100 };
+            (Coord::new(44. + i as f64 * 1e-4, 15.), Some(elev))
+        })
+        .collect();
+    let simplified = super::simplify_with_elevation(&track, 10., 5.);
+    let kept: Vec<usize> = simplified
+        .iter()
+        .map(|point| track.iter().position(|p| p == point).unwrap())
+        .collect();
+    assert_eq!(kept, [0, 49, 50, 51, 100]);
+
+    // the spike is within the vertical tolerance
+    let simplified = super::simplify_with_elevation(&track, 10., 50.);
+    assert_eq!(simplified, [track[0], track[100]]);
+
+    // a missing elevation only counts horizontally
+    let mut voids = track.clone();
+    voids[50].1 = None;
+    assert_eq!(
+        super::simplify_with_elevation(&voids, 10., 5.),
+        [track[0], track[100]]
+    );
+
+    // a sideways spike of about 80m is kept, regardless of elevation
+    let mut detour = voids;
+    detour[30].0.lon += 1e-3;
+    assert_eq!(
+        super::simplify_with_elevation(&detour, 10., 5.),
+        [track[0], track[29], detour[30], track[31], track[100]]
+    );
+    assert_eq!(
+        super::simplify_with_elevation(&track[..2], 0., 0.),
+        track[..2]
+    );
+}
+
+#[test]
+fn raw_at() {
+    let mut tile = synthetic_tile(|row, col| (row + col) as i16);
+    tile.data[0] = tiles::VOID;
+    let void = Coord::new(44.9999, 15.0001);
+    assert_eq!(tile.raw_at(void), Some(tiles::VOID));
+    assert_eq!(tile.lookup(void), Lookup::Void);
+    assert_eq!(tile.raw_at((44.0001, 15.9999)), Some(2400));
+    assert_eq!(tile.raw_at((44.5, 14.5)), None);
+}
+
+#[test]
+fn mean_in() {
+    let mut tile = synthetic_tile(|row, col| (row * 10 + col) as i16);
+    // rows 600..=900 and cols 0..=300: a mean of 7500 + 150
+    let (sw, ne) = (Coord::new(44.25, 14.5), Coord::new(44.5, 15.25));
+    assert_eq!(tile.mean_in(sw, ne), Some(7650.));
+    // voids don't count
+    tile.data[600 * 1201] = tiles::VOID;
+    let mean = tile.mean_in(sw, ne).unwrap();
+    assert!(mean > 7650. && mean < 7650.1, "{mean}");
+
+    // a box between the samples gets the nearest one
+    let step = 1. / 1200.;
+    let (sub_sw, sub_ne) = (
+        Coord::new(44.5 + step / 4., 15.5 + step / 4.),
+        Coord::new(44.5 + step / 2., 15.5 + step / 2.),
+    );
+    assert_eq!(tile.mean_in(sub_sw, sub_ne), Some(6600.));
+
+    tile.data.fill(tiles::VOID);
+    assert_eq!(tile.mean_in(sw, ne), None);
+    assert_eq!(
+        tile.mean_in(Coord::new(46., 15.), Coord::new(47., 16.)),
+        None
+    );
+}
+
+#[test]
+fn tile_builder() {
+    let res = Resolution::SRTM3;
+    let mut builder = TileBuilder::new(44, 15, res);
+    assert_eq!(builder.push_row(&[0; 1200]), Err(Error::filesize()));
+    for row in 0..res.extent() {
+        let row: Vec<i16> = (0..res.extent()).map(|col| (row + col) as i16).collect();
+        builder.push_row(&row).unwrap();
+    }
+    assert_eq!(builder.rows(), res.extent());
+    assert_eq!(builder.push_row(&[0; 1201]), Err(Error::filesize()));
+    let tile = builder.build().unwrap();
+    assert!(tile == synthetic_tile(|row, col| (row + col) as i16));
+    assert_eq!(tile.elevation((44.5, 15.5)), Some(1200));
+
+    let mut partial = TileBuilder::new(44, 15, res);
+    partial.push_row(&[0; 1201]).unwrap();
+    assert_eq!(partial.build(), Err(Error::filesize()));
+    let mut invalid = TileBuilder::new(90, 15, res);
+    (0..res.extent()).for_each(|_| invalid.push_row(&[0; 1201]).unwrap());
+    assert_eq!(invalid.build(), Err(Error::ParseLatLong));
+}
+
+#[test]
+fn insolation() {
+    // rising by 20m per sample to the north or south, about 13°
+    let facing_south = synthetic_tile(|row, _| ((1200 - row) * 20) as i16);
+    let facing_north = synthetic_tile(|row, _| (row * 20) as i16);
+    let flat = synthetic_tile(|_, _| 100);
+    let coord = Coord::new(44.5, 15.5);
+    let south = facing_south.insolation(coord, 44.5).unwrap();
+    let north = facing_north.insolation(coord, 44.5).unwrap();
+    let level = flat.insolation(coord, 44.5).unwrap();
+    assert!(north < level && level < south, "{north} {level} {south}");
+    assert!(south <= 1.);
+    // in the southern hemisphere, the sun is to the north
+    let south = facing_south.insolation(coord, -44.5).unwrap();
+    let north = facing_north.insolation(coord, -44.5).unwrap();
+    assert!(south < north, "{south} {north}");
+
+    assert_eq!(flat.insolation((44.9999, 15.5), 44.5), None);
+    assert_eq!(flat.insolation((43.5, 15.5), 44.5), None);
+}
+
+#[test]
+fn flow_direction() {
+    let extent = Resolution::SRTM3.extent();
+    // a plane rising to the north-west, so everything flows south-east, but the edges
+    let plane = synthetic_tile(|row, col| (5000 - row - col) as i16);
+    let flow = plane.flow_direction();
+    assert_eq!(flow.len(), plane.data.len());
+    for (i, dir) in flow.iter().enumerate() {
+        let (row, col) = (i / extent, i % extent);
+        let edge = row == 0 || col == 0 || row == extent - 1 || col == extent - 1;
+        assert_eq!(*dir, if edge { 0 } else { 2 }, "{row} {col}");
+    }
+    // samples are closer east-west than north-south at this latitude, so east is the steepest,
+    // though south-east drops more per sample interval
+    let tilted = synthetic_tile(|row, col| (20000 - row * 5 - col * 10) as i16);
+    assert_eq!(tilted.flow_direction()[600 * extent + 600], 1);
+    let west = synthetic_tile(|_, col| col as i16 * 10);
+    assert_eq!(west.flow_direction()[600 * extent + 600], 5);
+
+    let mut sink = synthetic_tile(|_, _| 100);
+    assert!(sink.flow_direction().iter().all(|dir| *dir == 0));
+    sink.data[600 * extent + 600] = 90;
+    sink.data[600 * extent + 602] = tiles::VOID;
+    let flow = sink.flow_direction();
+    assert_eq!(flow[600 * extent + 600], 0);
+    assert_eq!(flow[600 * extent + 599], 1);
+    // next to the void
+    assert_eq!(flow[600 * extent + 601], 0);
+    assert_eq!(flow[599 * extent + 600], 3);
+    assert_eq!(flow[601 * extent + 599], 8);
+}
+
+#[test]
+fn multidirectional_hillshade() {
+    let extent = Resolution::SRTM3.extent();
+    let mut flat = synthetic_tile(|_, _| 100);
+    flat.data[600 * extent + 600] = tiles::VOID;
+    let shade = flat.multidirectional_hillshade(45.);
+    assert_eq!(shade.len(), flat.data.len());
+    // sin(45°) * 254 + 1
+    let lit = 181;
+    for (i, value) in shade.iter().enumerate() {
+        let (row, col) = (i / extent, i % extent);
+        let undefined = row == 0 || col == 0 || row == extent - 1 || col == extent - 1;
+        let near_void = row.abs_diff(600) <= 1 && col.abs_diff(600) <= 1;
+        let expected = if undefined || near_void {
+            HILLSHADE_VOID
+        } else {
+            lit
+        };
+        assert_eq!(*value, expected, "{row} {col}");
+    }
+    // facing the light is brighter than facing away from it
+    let facing_west = synthetic_tile(|_, col| col as i16 * 20);
+    let facing_east = synthetic_tile(|_, col| (1200 - col) as i16 * 20);
+    let center = 600 * extent + 600;
+    assert!(facing_west.multidirectional_hillshade(45.)[center] > lit);
+    assert!(facing_east.multidirectional_hillshade(45.)[center] < lit);
+}
+
+#[test]
+fn web_mercator() {
+    let close = |a: f64, b: f64, tol: f64| (a - b).abs() < tol;
+    // the corner of the map, where x and y meet at half the circumference
+    let (x, y) = Coord::new(coords::WEB_MERCATOR_MAX_LAT, 180.).to_web_mercator();
+    assert!(close(x, 20_037_508.342_789_244, 1e-6), "{x}");
+    assert!(close(y, 20_037_508.342_789_244, 1e-6), "{y}");
+    let corner = Coord::from_web_mercator(-x, -y);
+    assert!(close(corner.lat, -coords::WEB_MERCATOR_MAX_LAT, 1e-9));
+    assert!(close(corner.lon.abs(), 180., 1e-9));
+
+    let veli_brig = Coord::new(44.4480403, 15.0733053);
+    let (x, y) = veli_brig.to_web_mercator();
+    assert!(
+        close(x, 1_677_952.67, 0.01) && close(y, 5_535_041.28, 0.01),
+        "{x} {y}"
+    );
+    let back = Coord::from_web_mercator(x, y);
+    assert!(close(back.lat, veli_brig.lat, 1e-9) && close(back.lon, veli_brig.lon, 1e-9));
+
+    // clamped towards the poles, and past the antimeridian
+    assert_eq!(
+        Coord::new(89.9, 0.).to_web_mercator(),
+        Coord::new(coords::WEB_MERCATOR_MAX_LAT, 0.).to_web_mercator()
+    );
+    assert!(close(
+        Coord::from_web_mercator(x + 2. * 20_037_508.342_789_244, y).lon,
+        veli_brig.lon,
+        1e-9
+    ));
+}
+
+#[test]
+fn from_file_sniffs_compression() {
+    let dir = std::env::temp_dir().join("srtm_reader_sniffs_compression");
+    std::fs::create_dir_all(&dir).unwrap();
+    let tile = synthetic_tile(|row, col| (row * 2 + col) as i16);
+    let path = dir.join("N44E015.hgt");
+
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::Write;
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(&tile.as_be_bytes()).unwrap();
+        std::fs::write(&path, gz.finish().unwrap()).unwrap();
+        assert!(Tile::from_file(&path) == Ok(tile.clone()));
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(Tile::from_bytes(&bytes, 44, 15) == Ok(tile.clone()));
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(&tile.as_be_bytes()[..1000]).unwrap();
+        std::fs::write(&path, gz.finish().unwrap()).unwrap();
+        assert_eq!(
+            Tile::from_file(&path),
+            Err(Error::filesize().in_file(&path))
+        );
+
+        // a trailer claiming the length of a whole tile, for truncated content
+        let mut bytes = std::fs::read(&path).unwrap();
+        let len = bytes.len();
+        let full_len = tile.as_be_bytes().len() as u32;
+        bytes[len - 4..].copy_from_slice(&full_len.to_le_bytes());
+        assert_eq!(Tile::from_bytes(&bytes, 44, 15), Err(Error::Archive));
+    }
+    #[cfg(not(feature = "gzip"))]
+    {
+        std::fs::write(&path, [0x1f, 0x8b, 8, 0, 0, 0]).unwrap();
+        assert_eq!(Tile::from_file(&path), Err(Error::Archive));
+    }
+    // neither compressed, nor of a known size
+    std::fs::write(&path, &tile.as_be_bytes()[2..]).unwrap();
+    assert_eq!(
+        Tile::from_file(&path),
+        Err(Error::filesize().in_file(&path))
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn count_above_and_below() {
+    // the northern half at 2500m, the southern at 500m, but the middle row at 2000m
+    let mut tile = synthetic_tile(|row, _| match row {
+        0..600 => 2500,
+        600 => 2000,
+        _ => 500,
+    });
+    tile.data[0] = tiles::VOID;
+    let (half, row) = (600 * 1201, 1201);
+    assert_eq!(tile.count_above(2000), half - 1);
+    assert_eq!(tile.count_above(1999), half - 1 + row);
+    assert_eq!(tile.count_below(2000), half);
+    assert_eq!(tile.count_below(-9999), 0);
+    assert_eq!(tile.count_above(tiles::VOID), tile.data.len() - 1);
+
+    // about half of a tile of 111km by 79km
+    let area = tile.area_above_km2(2000);
+    assert!((4_350. ..4_450.).contains(&area), "{area}");
+    assert_eq!(tile.area_above_km2(3000), 0.);
+}
+
+#[test]
+fn content_hash() {
+    let tile = synthetic_tile(|row, col| (row ^ col) as i16);
+    let hash = tile.content_hash();
+    assert_eq!(
+        synthetic_tile(|row, col| (row ^ col) as i16).content_hash(),
+        hash
+    );
+    assert_eq!(tile.clone().with_zero_as_void(true).content_hash(), hash);
+
+    let mut changed = tile.clone();
+    changed.data[1_000_000] += 1;
+    assert_ne!(changed.content_hash(), hash);
+    let moved = Tile::new(45, 15, tile.resolution, tile.data.clone());
+    assert_ne!(moved.content_hash(), hash);
+
+    // FNV-1a of the header alone, to pin the algorithm
+    let tiny = Tile::new(0, 0, Resolution::SRTM3, Vec::new());
+    assert_eq!(tiny.content_hash(), 0x777d_d114_b67c_6440);
+}
+
+#[test]
+fn parse_matches_from_be_bytes() {
+    let bytes = std::fs::read("N44E015.hgt").unwrap();
+    let expected: Vec<i16> = bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    let parsed = Tile::parse_hgt(bytes.as_slice(), Resolution::SRTM1).unwrap();
+    assert!(parsed == expected);
+}
+
+#[test]
+fn simd_byte_swap_matches_scalar() {
+    let bytes = std::fs::read("N44E015.hgt").unwrap();
+    let mut scalar = Vec::new();
+    byte_swap::extend_from_be_scalar(&mut scalar, &bytes);
+
+    let check = |extend: &dyn Fn(&mut Vec<i16>, &[u8])| {
+        // not a whole number of 16 sample chunks, or samples, appended after what's there already
+        for len in [bytes.len(), bytes.len() - 2, 65, 64, 33, 31, 1, 0] {
+            let mut elevations = vec![7];
+            extend(&mut elevations, &bytes[..len]);
+            assert_eq!(elevations[0], 7);
+            assert!(elevations[1..] == scalar[..len / 2]);
+        }
+    };
+    check(&byte_swap::extend_from_be);
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if std::is_x86_feature_detected!("avx2") {
+        // SAFETY: the CPU supports AVX2
+        check(&|out, bytes| unsafe { byte_swap::extend_from_be_avx2(out, bytes) });
+    }
+}
+
+#[test]
+fn iter_valid() {
+    let mut tile = synthetic_tile(|row, col| (row + col) as i16);
+    tile.data[0] = tiles::VOID;
+    tile.data[1202] = i16::MIN;
+    let valid: Vec<(Coord, i16)> = tile.iter_valid().collect();
+    assert_eq!(valid.len(), tile.data.len() - 2);
+    assert_eq!(valid[0], (tile.coord_at(0, 1), 1));
+    assert_eq!(valid[1200], (tile.coord_at(1, 0), 1));
+    assert_eq!(valid[1201], (tile.coord_at(1, 2), 3));
+    assert_eq!(valid.last(), Some(&(Coord::new(44., 16.), 2400)));
+    assert!(valid.iter().all(|(_, elev)| !tiles::is_void(*elev)));
+}
+
+#[test]
+fn regrid_onto() {
+    let tile = synthetic_tile(|row, col| (row * 2 + col) as i16);
+    // onto its own grid, every sample is hit exactly
+    let same = Tile::new(44, 15, Resolution::SRTM3, Vec::new());
+    assert!(tile.regrid_onto(&same) == tile);
+    // the elevations of `target` aren't needed, and a whole SRTM1 grid is slow in debug builds
+    let srtm1 = Tile::new(44, 15, Resolution::SRTM1, Vec::new());
+    // from (1, 1) to (1, 2) of `tile`, a third of the way at a time
+    assert_eq!(tile.regridded_at(&srtm1, 3, 4), 3);
+    assert_eq!(tile.regridded_at(&srtm1, 3, 5), 4);
+    assert_eq!(tile.regridded_at(&srtm1, 3, 6), 4);
+
+    // only the shared western edge of an eastern neighbour is covered
+    let east = tile.regrid_onto(&Tile::new(44, 16, Resolution::SRTM3, Vec::new()));
+    let extent = Resolution::SRTM3.extent();
+    for (i, elev) in east.data.iter().enumerate() {
+        let (row, col) = (i / extent, i % extent);
+        let expected = if col == 0 {
+            tile.data[row * extent + 1200]
+        } else {
+            tiles::VOID
+        };
+        assert_eq!(*elev, expected, "{row} {col}");
+    }
+
+    let mut voids = tile.clone();
+    voids.data[1202] = tiles::VOID;
+    assert_eq!(voids.regridded_at(&srtm1, 3, 3), tiles::VOID);
+    assert_eq!(voids.regridded_at(&srtm1, 3, 4), tiles::VOID);
+    assert_eq!(voids.regridded_at(&srtm1, 3, 6), 4);
+}
+
+#[test]
+fn get_interpolated() {
+    let mut tile = synthetic_tile(|row, col| (row * 10 + col * 3) as i16);
+    let step = Resolution::SRTM3.cell_size_deg();
+    // a quarter of the way south from row 600, halfway east from col 300
+    let coord = Coord::new(44.5 - step / 4., 15.25 + step / 2.);
+    let elev = tile.get_interpolated(coord).unwrap();
+    assert!((elev - (6002.5 + 901.5)).abs() < 1e-6, "{elev}");
+    // on a sample, it's the sample
+    let on_sample = tile.get_interpolated((44.5, 15.25)).unwrap();
+    assert!((on_sample - 6900.).abs() < 1e-6, "{on_sample}");
+    // differs from the nearest sample that `get` takes
+    assert_eq!(tile.elevation(coord), Some(6900));
+
+    tile.data[601 * 1201 + 301] = tiles::VOID;
+    assert_eq!(tile.get_interpolated(coord), None);
+    assert!(tile.get_interpolated((44.5, 15.25)).is_some());
+    assert_eq!(tile.get_interpolated((45.5, 15.25)), None);
+    assert_eq!(tile.get_interpolated((44.5, 14.99)), None);
+}
+
+#[test]
+fn error_trait() {
+    fn load(name: &str) -> Result<Tile, Box<dyn std::error::Error>> {
+        Ok(Tile::from_file(name)?)
+    }
+    let err = load("N00E000.hgt").unwrap_err();
+    assert_eq!(err.to_string(), "tile file not found: N00E000.hgt");
+    assert_eq!(
+        err.downcast_ref::<Error>(),
+        Some(&Error::open("N00E000.hgt", io::ErrorKind::NotFound.into()))
+    );
+    assert!(load("N44E015.hgt").is_ok());
+}
+
+#[test]
+fn error_carries_path_and_source() {
+    use std::error::Error as _;
+
+    let err = Tile::from_file("N00E000.hgt").unwrap_err();
+    let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::NotFound);
+
+    let err = Resolution::of_file("Cargo.toml").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "size of Cargo.toml doesn't match any known resolution"
+    );
+    assert!(err.source().is_none());
+
+    // failing to open for another reason than a missing file isn't reported as not found
+    let path = Path::new("Cargo.toml").join("N44E015.hgt");
+    let err = Tile::from_file(&path).unwrap_err();
+    assert!(
+        matches!(&err, Error::Read { path: Some(p), source: Some(source) }
+            if *p == path && source.kind() != io::ErrorKind::NotFound),
+        "{err:?}"
+    );
+    assert_eq!(
+        Resolution::of_file(&path).unwrap_err().to_string(),
+        format!("couldn't read elevation data from {}", path.display())
+    );
+
+    let err = Error::read(io::ErrorKind::UnexpectedEof.into()).in_file("N44E015.hgt");
+    assert!(err.to_string().ends_with(" from N44E015.hgt"));
+    assert!(err.source().is_some());
 }
@@ -0,0 +1,511 @@
+use super::{coords::EARTH_RADIUS, Coord, Error, Lookup, Resolution, Tile};
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, OnceLock, PoisonError,
+    },
+};
+#[cfg(feature = "tokio")]
+use {
+    std::io,
+    tokio::{sync::Semaphore, task::JoinSet},
+};
+
+/// a sample of a [`TileSet::grade_profile()`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradePoint {
+    pub coord: Coord,
+    /// distance from the start of the path, in meters
+    pub distance: f64,
+    /// `None` if there's no valid elevation data for `coord`
+    pub elevation: Option<i16>,
+    /// rise over run since the previous sample, in percent
+    /// `None` for the first sample, or if either elevation is missing
+    pub grade: Option<f64>,
+}
+
+/// a collection of [`Tile`]s, at most one for each location
+///
+/// # Conflicts
+///
+/// when a [`Tile`] is added for a location that's already covered,
+/// the one with the finer [`crate::Resolution`] is kept,
+/// on equal resolutions, the newly added one replaces the old one
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TileSet {
+    tiles: HashMap<(i8, i16), Tile>,
+}
+
+impl TileSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add `tile` to the set, according to the conflict policy of [`TileSet`]
+    pub fn insert(&mut self, tile: Tile) {
+        let key = (tile.latitude, tile.longitude);
+        match self.tiles.get(&key) {
+            Some(existing) if existing.resolution.is_finer_than(tile.resolution) => {}
+            _ => {
+                self.tiles.insert(key, tile);
+            }
+        }
+    }
+    /// add all tiles of `other` to `self`, according to the conflict policy of [`TileSet`]
+    pub fn extend(&mut self, other: TileSet) {
+        other.tiles.into_values().for_each(|tile| self.insert(tile));
+    }
+    /// combine `self` and `other`, according to the conflict policy of [`TileSet`]
+    /// tiles of `other` count as the newly added ones
+    pub fn merge(mut self, other: TileSet) -> TileSet {
+        self.extend(other);
+        self
+    }
+
+    /// load the tiles containing `coords` from `dir`
+    /// fails if any of the tiles can't be loaded
+    pub fn load_dir<P: AsRef<Path>>(dir: P, coords: &[Coord]) -> Result<TileSet, Error> {
+        Self::load_dir_with_progress(dir, coords, |_, _| {}, &AtomicBool::new(false))
+    }
+    /// same as [`TileSet::load_dir()`], but calls `progress` with the number of loaded and all tiles after each one,
+    /// and stops early once `cancel` is set, returning the tiles loaded so far
+    pub fn load_dir_with_progress<P: AsRef<Path>>(
+        dir: P,
+        coords: &[Coord],
+        progress: impl FnMut(usize, usize),
+        cancel: &AtomicBool,
+    ) -> Result<TileSet, Error> {
+        let keys = coords.iter().map(Coord::tile_key).collect();
+        Self::load_keys(dir.as_ref(), keys, false, progress, cancel)
+    }
+    /// load all tiles between the `sw` and `ne` corners from `dir`
+    /// tiles without a file are skipped, eg. over the sea
+    pub fn load_bbox<P: AsRef<Path>>(dir: P, sw: Coord, ne: Coord) -> Result<TileSet, Error> {
+        Self::load_bbox_with_progress(dir, sw, ne, |_, _| {}, &AtomicBool::new(false))
+    }
+    /// same as [`TileSet::load_bbox()`], with `progress` and `cancel` as in [`TileSet::load_dir_with_progress()`]
+    pub fn load_bbox_with_progress<P: AsRef<Path>>(
+        dir: P,
+        sw: Coord,
+        ne: Coord,
+        progress: impl FnMut(usize, usize),
+        cancel: &AtomicBool,
+    ) -> Result<TileSet, Error> {
+        let ((south, west), (north, east)) = (sw.tile_key(), ne.tile_key());
+        let keys = (south..=north)
+            .flat_map(|lat| (west..=east).map(move |lon| (lat, lon)))
+            .collect();
+        Self::load_keys(dir.as_ref(), keys, true, progress, cancel)
+    }
+
+    /// load the tiles containing `coords` from `dir` asynchronously, reading at most `concurrency` files at once
+    /// fails if any of the tiles can't be loaded
+    #[cfg(feature = "tokio")]
+    pub async fn load_dir_async<P: AsRef<Path>>(
+        dir: P,
+        coords: &[Coord],
+        concurrency: usize,
+    ) -> Result<TileSet, Error> {
+        let limit = Arc::new(Semaphore::new(concurrency.max(1)));
+        let needed = coords.iter().map(Coord::tile_key).collect::<BTreeSet<_>>();
+
+        let mut loads = JoinSet::new();
+        let mut paths = HashMap::new();
+        for key in needed {
+            let path = dir.as_ref().join(Coord::from(key).get_filename());
+            let limit = Arc::clone(&limit);
+            let task_path = path.clone();
+            let task = loads.spawn(async move {
+                let _permit = limit
+                    .acquire_owned()
+                    .await
+                    .map_err(|e| Error::read(io::Error::other(e)).in_file(&task_path))?;
+                Tile::from_file_async(task_path).await
+            });
+            paths.insert(task.id(), path);
+        }
+
+        let mut set = TileSet::new();
+        while let Some(loaded) = loads.join_next_with_id().await {
+            let tile = match loaded {
+                Ok((_, tile)) => tile?,
+                // the task panicked or was cancelled, the `JoinError` tells which
+                Err(e) => {
+                    let path = paths.remove(&e.id());
+                    let err = Error::read(io::Error::other(e));
+                    return Err(match path {
+                        Some(path) => err.in_file(path),
+                        None => err,
+                    });
+                }
+            };
+            set.insert(tile);
+        }
+        Ok(set)
+    }
+
+    /// the [`Tile`] that contains `coord`, if it's in the set
+    pub fn tile(&self, coord: impl Into<Coord>) -> Option<&Tile> {
+        let coord = coord.into();
+        self.tiles
+            .get(&coord.tile_key())
+            .filter(|tile| tile.contains(coord))
+    }
+    /// get the elevation of this `coord` from the [`Tile`] that contains it
+    pub fn elevation(&self, coord: impl Into<Coord>) -> Option<i16> {
+        let coord = coord.into();
+        self.tile(coord)?.elevation(coord)
+    }
+
+    /// elevations along `path`, sampled about every `step_m` meters, with the grade between samples
+    ///
+    /// every point of `path` is sampled as well, segments between them are treated as straight lines in latitude/longitude
+    /// empty if `step_m` isn't positive
+    pub fn grade_profile(&self, path: &[Coord], step_m: f64) -> Vec<GradePoint> {
+        let mut profile: Vec<GradePoint> = Vec::new();
+        for (coord, distance) in sample_path(path, step_m) {
+            let elevation = self.elevation(coord);
+            let grade = profile.last().and_then(|prev| {
+                let run = distance - prev.distance;
+                let rise = elevation? - prev.elevation?;
+                (run > 0.).then(|| rise as f64 / run * 100.)
+            });
+            profile.push(GradePoint {
+                coord,
+                distance,
+                elevation,
+                grade,
+            });
+        }
+        profile
+    }
+
+    /// a single raster of the area between the `sw` and `ne` corners, sampled from the tiles of the set
+    /// at the spacing of `res`, the nearest sample of the [`Tile`] containing each point is taken
+    ///
+    /// returns the grid in row-major order from the north-west corner, as [`Tile::data`], with the number of rows and columns
+    /// points in missing tiles, or on voids are `None`
+    pub fn elevation_grid(
+        &self,
+        sw: impl Into<Coord>,
+        ne: impl Into<Coord>,
+        res: Resolution,
+    ) -> (Vec<Option<i16>>, usize, usize) {
+        let (sw, ne) = (sw.into(), ne.into());
+        let cell = res.cell_size_deg();
+        let count = |from: f64, to: f64| ((to - from) / cell).round().max(0.) as usize + 1;
+        let (rows, cols) = (count(sw.lat, ne.lat), count(sw.lon, ne.lon));
+        let grid = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                self.lookup(Coord {
+                    lat: ne.lat - row as f64 * cell,
+                    lon: sw.lon + col as f64 * cell,
+                })
+            })
+            .collect();
+        (grid, rows, cols)
+    }
+
+    /// which grid points within `radius_m` meters of `observer` can be seen from `observer_height_m` meters above the ground
+    ///
+    /// the grid is the one of the [`Tile`] containing `observer`: an empty `Vec` is returned if it's not in the set, or is void there.
+    /// each point is checked with a line of sight sampled about every cell, if `curvature`, the drop of the Earth's surface is accounted for.
+    /// points in missing tiles, or on voids are not visible, missing samples along a line of sight don't block it
+    pub fn viewshed(
+        &self,
+        observer: impl Into<Coord>,
+        observer_height_m: f64,
+        radius_m: f64,
+        curvature: bool,
+    ) -> Vec<(Coord, bool)> {
+        let observer = observer.into();
+        let Some(tile) = self.tile(observer) else {
+            return Vec::new();
+        };
+        let Lookup::Valid(ground) = tile.lookup(observer) else {
+            return Vec::new();
+        };
+        let eye = ground as f64 + observer_height_m;
+        let cell = tile.resolution.cell_size_deg();
+        // height of the line of sight between `eye` and `elev` at `distance`, relative to `eye`
+        let height = |elev: i16, distance: f64| {
+            let drop = if curvature {
+                distance * distance / (2. * EARTH_RADIUS)
+            } else {
+                0.
+            };
+            elev as f64 - drop - eye
+        };
+
+        let d_lat = (radius_m / EARTH_RADIUS).to_degrees();
+        // at most all the way around, at or next to a pole
+        let d_lon = (d_lat / observer.lat.to_radians().cos()).min(180.);
+        // grid points beyond the poles or the antimeridian are left out
+        let index = |deg: f64| (deg / cell).ceil() as i64;
+        let (lat_from, lat_to) = (
+            index(observer.lat - d_lat).max(index(-90.)),
+            ((observer.lat + d_lat) / cell).floor().min(90. / cell) as i64,
+        );
+        let (lon_from, lon_to) = (
+            index(observer.lon - d_lon).max(index(-180.)),
+            ((observer.lon + d_lon) / cell).floor().min(180. / cell) as i64,
+        );
+        let mut points = Vec::new();
+        for lat in lat_from..=lat_to {
+            for lon in lon_from..=lon_to {
+                let target = Coord::new(lat as f64 * cell, lon as f64 * cell);
+                let distance = observer.distance_to(target);
+                if distance > radius_m {
+                    continue;
+                }
+                let visible = self.lookup(target).is_some_and(|elev| {
+                    let slope = height(elev, distance) / distance;
+                    // a sample for every cell crossed along the larger of the 2 axes
+                    let cells = (target.lat - observer.lat)
+                        .abs()
+                        .max((target.lon - observer.lon).abs())
+                        / cell;
+                    let steps = cells.ceil() as usize;
+                    distance == 0.
+                        || (1..steps).all(|step| {
+                            let t = step as f64 / steps as f64;
+                            let sample = Coord {
+                                lat: observer.lat + (target.lat - observer.lat) * t,
+                                lon: observer.lon + (target.lon - observer.lon) * t,
+                            };
+                            self.lookup(sample).is_none_or(|elev| {
+                                height(elev, distance * t) / (distance * t) <= slope
+                            })
+                        })
+                });
+                points.push((target, visible));
+            }
+        }
+        points
+    }
+
+    /// the highest valid sample within `radius_m` meters of `from`, in the sector of `bearing_deg` ± `half_angle_deg`
+    /// bearings are in degrees clockwise from north, every tile of the set around `from` is searched
+    ///
+    /// `from` itself isn't part of any sector, on equal elevations, the nearest sample wins
+    /// `None` if there's no valid sample in the sector
+    pub fn highest_in_sector(
+        &self,
+        from: impl Into<Coord>,
+        bearing_deg: f64,
+        half_angle_deg: f64,
+        radius_m: f64,
+    ) -> Option<(Coord, i16)> {
+        let from = from.into();
+        let d_lat = (radius_m / EARTH_RADIUS).to_degrees();
+        let d_lon = d_lat / from.lat.to_radians().cos();
+        let mut highest: Option<(Coord, i16, f64)> = None;
+        let sw = Coord {
+            lat: from.lat - d_lat,
+            lon: from.lon - d_lon,
+        };
+        let ne = Coord {
+            lat: from.lat + d_lat,
+            lon: from.lon + d_lon,
+        };
+        for tile in self.tiles() {
+            let Some((top, left, bottom, right)) = tile.pixel_bounds(sw, ne) else {
+                continue;
+            };
+            for row in top..=bottom {
+                for col in left..=right {
+                    let elev = tile.data[row * tile.resolution.extent() + col];
+                    if tile.treats_as_void(elev) {
+                        continue;
+                    }
+                    let coord = tile.coord_at(row, col);
+                    let distance = from.distance_to(coord);
+                    if distance > radius_m {
+                        continue;
+                    }
+                    let off_bearing =
+                        (from.bearing_to(coord) - bearing_deg + 540.).rem_euclid(360.) - 180.;
+                    if distance == 0. || off_bearing.abs() > half_angle_deg {
+                        continue;
+                    }
+                    let higher = highest.is_none_or(|(_, max, nearest)| {
+                        elev > max || (elev == max && distance < nearest)
+                    });
+                    if higher {
+                        highest = Some((coord, elev, distance));
+                    }
+                }
+            }
+        }
+        highest.map(|(coord, elev, _)| (coord, elev))
+    }
+
+    /// the number of tiles in the set
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+    /// all tiles in the set, in no particular order
+    pub fn tiles(&self) -> impl Iterator<Item = &Tile> {
+        self.tiles.values()
+    }
+}
+
+// impl for non-pub fn-s
+impl TileSet {
+    /// the valid elevation at `coord`, without the warnings of [`Tile::get()`]
+    fn lookup(&self, coord: Coord) -> Option<i16> {
+        match self.tile(coord)?.lookup(coord) {
+            Lookup::Valid(elev) => Some(elev),
+            Lookup::Void | Lookup::OutsideTile => None,
+        }
+    }
+    /// load the tiles at `keys` from `dir`, see [`TileSet::load_dir_with_progress()`]
+    fn load_keys(
+        dir: &Path,
+        keys: BTreeSet<(i8, i16)>,
+        skip_missing: bool,
+        mut progress: impl FnMut(usize, usize),
+        cancel: &AtomicBool,
+    ) -> Result<TileSet, Error> {
+        let mut set = TileSet::new();
+        for (i, key) in keys.iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            let path = dir.join(Coord::from(*key).get_filename());
+            match Tile::from_file(path) {
+                Ok(tile) => set.insert(tile),
+                Err(Error::NotFound { .. }) if skip_missing => {}
+                Err(e) => return Err(e),
+            }
+            progress(i + 1, keys.len());
+        }
+        Ok(set)
+    }
+}
+
+/// collects tiles according to the conflict policy of [`TileSet`], as [`TileSet::insert()`] would
+impl FromIterator<Tile> for TileSet {
+    fn from_iter<I: IntoIterator<Item = Tile>>(iter: I) -> Self {
+        let mut set = TileSet::new();
+        iter.into_iter().for_each(|tile| set.insert(tile));
+        set
+    }
+}
+
+/// a thread-safe registry of [`Tile`]s loaded on demand from a directory, handing out shared references
+///
+/// each tile is loaded at most once and shared by everyone who asked for it,
+/// so concurrent handlers of a long-running service don't duplicate the buffers
+#[derive(Debug, Default)]
+pub struct SharedTileSet {
+    dir: PathBuf,
+    tiles: Mutex<HashMap<(i8, i16), Arc<TileSlot>>>,
+}
+
+/// a [`Tile`] of a [`SharedTileSet`], filled in by the first thread that wants it
+type TileSlot = OnceLock<Result<Arc<Tile>, Error>>;
+
+impl SharedTileSet {
+    /// an empty registry, loading tiles from `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            tiles: Mutex::default(),
+        }
+    }
+
+    /// the [`Tile`] that contains `coord`, loaded from the directory if it isn't yet
+    ///
+    /// a tile wanted by several threads is only read once, the others wait for it,
+    /// while tiles already loaded stay available to everyone else
+    /// a failed load isn't kept, the next call tries again
+    pub fn get(&self, coord: impl Into<Coord>) -> Result<Arc<Tile>, Error> {
+        let coord = coord.into();
+        let key = coord.tile_key();
+        let slot = Arc::clone(self.lock().entry(key).or_default());
+        let loaded =
+            slot.get_or_init(|| Tile::from_file(self.dir.join(coord.get_filename())).map(Arc::new));
+        if loaded.is_err() {
+            let mut tiles = self.lock();
+            if tiles.get(&key).is_some_and(|s| Arc::ptr_eq(s, &slot)) {
+                tiles.remove(&key);
+            }
+        }
+        loaded.clone()
+    }
+    /// the elevation of `coord`, see [`SharedTileSet::get()`]
+    pub fn elevation(&self, coord: impl Into<Coord>) -> Result<Option<i16>, Error> {
+        let coord = coord.into();
+        match self.get(coord)?.lookup(coord) {
+            Lookup::Valid(elev) => Ok(Some(elev)),
+            Lookup::Void | Lookup::OutsideTile => Ok(None),
+        }
+    }
+    /// drop the tiles no one else holds a reference to, tiles still in use are kept
+    /// returns the number of tiles dropped
+    pub fn evict_unused(&self) -> usize {
+        let mut tiles = self.lock();
+        let before = tiles.len();
+        tiles.retain(|_, slot| match slot.get() {
+            Some(Ok(tile)) => Arc::strong_count(tile) > 1,
+            // still loading, or just failed and about to be removed by the thread that loaded it
+            _ => Arc::strong_count(slot) > 1,
+        });
+        before - tiles.len()
+    }
+    /// the number of tiles loaded
+    pub fn len(&self) -> usize {
+        self.lock()
+            .values()
+            .filter(|slot| matches!(slot.get(), Some(Ok(_))))
+            .count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// impl for non-pub fn-s
+impl SharedTileSet {
+    /// the map of slots, only ever locked briefly, never while loading
+    fn lock(&self) -> MutexGuard<'_, HashMap<(i8, i16), Arc<TileSlot>>> {
+        self.tiles.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// points along `path`, about `step_m` meters apart, with their distance from the start
+/// includes all points of `path`, empty unless `step_m` is positive
+///
+/// samples are never closer than the cells of the finest [`Resolution`], whatever `step_m` is
+fn sample_path(path: &[Coord], step_m: f64) -> Vec<(Coord, f64)> {
+    let (Some(first), true) = (path.first(), step_m > 0.) else {
+        return Vec::new();
+    };
+    let cell = Resolution::SRTM05.cell_size_deg();
+    let mut samples = vec![(*first, 0.)];
+    let mut distance = 0.;
+    for segment in path.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let length = from.distance_to(to);
+        let cells = (to.lat - from.lat).abs().max((to.lon - from.lon).abs()) / cell;
+        let steps = (length / step_m).min(cells).ceil().max(1.) as usize;
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            let coord = Coord {
+                lat: from.lat + (to.lat - from.lat) * t,
+                lon: from.lon + (to.lon - from.lon) * t,
+            };
+            samples.push((coord, distance + length * t));
+        }
+        distance += length;
+    }
+    samples
+}
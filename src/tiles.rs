@@ -1,14 +1,107 @@
 use super::{Coord, Error};
-use crate::resolutions::Resolution;
+use crate::{byte_swap, resolutions::Resolution};
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::{
     fs::File,
-    io::{self, Read},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
     path::Path,
 };
 
+/// the value used by SRTM data to mark a missing elevation
+pub const VOID: i16 = -9999;
+
+/// whether `elev` is a marker for missing data, rather than an actual elevation
+pub const fn is_void(elev: i16) -> bool {
+    elev == VOID || elev == i16::MIN
+}
+
+/// [`is_void()`], optionally counting `0` as a void as well
+const fn is_void_with(elev: i16, zero_is_void: bool) -> bool {
+    is_void(elev) || (zero_is_void && elev == 0)
+}
+
+/// `elev` moved by `by`, clamped into `i16::MIN + 1..=i16::MAX`, and a meter back towards `elev` if it'd be a void
+fn offset_valid(elev: i16, by: i16, zero_is_void: bool) -> i16 {
+    let moved = elev.saturating_add(by).max(i16::MIN + 1);
+    if is_void_with(moved, zero_is_void) {
+        moved + (elev - moved).signum()
+    } else {
+        moved
+    }
+}
+
+/// `value` rounded to an elevation, a meter towards `value` if it'd be a void, eg. an interpolated `0`
+pub(crate) fn round_valid(value: f64, zero_is_void: bool) -> i16 {
+    let elev = value.round() as i16;
+    if !is_void_with(elev, zero_is_void) {
+        elev
+    } else if value < elev as f64 {
+        elev - 1
+    } else {
+        elev + 1
+    }
+}
+
+/// how far outside of a [`Tile`] a coordinate may be for [`Tile::get_clamped()`], in degrees, about 10 cm
+pub const CLAMP_TOLERANCE: f64 = 1e-6;
+
+/// the number of rows [`Tile::parse_hgt()`] buffers at once
+const PARSE_BUFFER_ROWS: usize = 16;
+/// the length of the longest `.hgt` content, of [`Resolution::SRTM05`]
+#[cfg(feature = "http")]
+const MAX_HGT_LEN: u64 = Resolution::SRTM05.total_len() as u64 * 2;
+
+/// lowest and highest elevations considered plausible on Earth, in meters
+const PLAUSIBLE_ELEVATIONS: std::ops::RangeInclusive<i16> = -500..=9000;
+/// with validation, more than this ratio of implausible elevations is rejected
+const MAX_IMPLAUSIBLE_RATIO: f64 = 0.01;
+
+/// options for parsing `.hgt` content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    /// reject data where too many valid elevations fall outside of -500..=9000 meters
+    /// catches swapped byte order and other garbage, that has the correct size
+    pub validate: bool,
+}
+
+/// the identity of a [`Tile`]: latitude, longitude and [`Resolution`], without its data
+/// cheap to copy, hash and compare, eg. for keying caches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TileId(pub i8, pub i16, pub Resolution);
+
+/// the result of [`Tile::lookup()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    /// a valid elevation
+    Valid(i16),
+    /// the coordinate is inside the [`Tile`], but there's no data for it
+    Void,
+    /// the coordinate isn't inside the [`Tile`]
+    OutsideTile,
+}
+
+/// an overview of a [`Tile`], see [`Tile::summary()`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileSummary {
+    pub latitude: i8,
+    pub longitude: i16,
+    pub resolution: Resolution,
+    pub center: Coord,
+    /// voids excluded, `None` if there are only voids
+    pub min: Option<i16>,
+    /// voids excluded, `None` if there are only voids
+    pub max: Option<i16>,
+    /// see [`Tile::void_ratio()`]
+    pub void_ratio: f64,
+}
+
 /// the SRTM tile, which contains the actual elevation data
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+///
+/// with the `serde` feature, `data` is serialized as the big-endian bytes of the `.hgt` file
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     /// north-south position of the [`Tile`]
     /// angle, ranges from −90° (south pole) to 90° (north pole), 0° is the Equator
@@ -17,7 +110,30 @@ pub struct Tile {
     /// angle, ranges from -180° to 180°
     pub longitude: i16,
     pub resolution: Resolution,
+    #[cfg_attr(feature = "serde", serde(with = "be_bytes"))]
     pub data: Vec<i16>,
+    /// see [`Tile::with_zero_as_void()`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    zero_is_void: bool,
+}
+
+/// tiles are equal if their location, [`Resolution`] and data are, [`Tile::zero_is_void()`] isn't compared
+impl PartialEq for Tile {
+    fn eq(&self, other: &Self) -> bool {
+        self.latitude == other.latitude
+            && self.longitude == other.longitude
+            && self.resolution == other.resolution
+            && self.data == other.data
+    }
+}
+impl Eq for Tile {}
+
+/// a valid [`Tile`] at `N00E000` of the default [`Resolution`], containing nothing but voids
+impl Default for Tile {
+    fn default() -> Self {
+        let res = Resolution::default();
+        Tile::new(0, 0, res, vec![VOID; res.total_len()])
+    }
 }
 
 // impl for pub fn-s
@@ -28,35 +144,314 @@ impl Tile {
             longitude: lon,
             resolution: res,
             data,
+            zero_is_void: false,
+        }
+    }
+    /// treat elevations of `0` as voids too, eg. to skip the sea in coastal data
+    /// off by default, as `0` is a valid elevation, it's not serialized
+    pub fn with_zero_as_void(self, zero_is_void: bool) -> Tile {
+        Tile {
+            zero_is_void,
+            ..self
+        }
+    }
+
+    /// same as [`Tile::new()`], but checks that `data` has the length `res` requires
+    /// and that `lat`, `lon` is a valid south-west corner of a tile
+    pub fn try_new(lat: i8, lon: i16, res: Resolution, data: Vec<i16>) -> Result<Tile, Error> {
+        let tile = Tile::new(lat, lon, res, data);
+        tile.validate()?;
+        Ok(tile)
+    }
+    /// check that this [`Tile`] is consistent: `data` has the length its `resolution` requires,
+    /// and `latitude`, `longitude` is a valid south-west corner of a tile
+    ///
+    /// # Errors
+    /// - [`Error::ParseLatLong`] if the corner isn't valid
+    /// - [`Error::Filesize`] if `data` has the wrong length
+    pub fn validate(&self) -> Result<(), Error> {
+        if !(-90..=89).contains(&self.latitude) || !(-180..=179).contains(&self.longitude) {
+            return Err(Error::ParseLatLong);
+        }
+        if self.data.len() != self.resolution.total_len() {
+            return Err(Error::filesize());
         }
+        Ok(())
     }
 
     /// read an srtm: `.hgt` file, and create a [`Tile`] if possible
+    ///
+    /// if the size of the file doesn't match any [`Resolution`], it's recognized by its content
+    /// as gzip or zip compressed, whatever its name, and decompressed with the `gzip` or `zip` feature,
+    /// the [`Resolution`] is inferred from the decompressed length then
+    ///
+    /// # Errors
+    /// [`Error::Archive`] if it's compressed, but the feature needed is disabled, or it can't be decompressed
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Tile, Error> {
-        let file = File::open(&path).map_err(|_| Error::NotFound)?;
+        Self::from_file_with(path, ParseOptions::default())
+    }
+    /// same as [`Tile::from_file()`], but parse according to `options`
+    pub fn from_file_with<P: AsRef<Path>>(path: P, options: ParseOptions) -> Result<Tile, Error> {
+        let mut file = File::open(&path).map_err(|e| Error::open(&path, e))?;
         // eprintln!("file: {file:?}");
+        let (lat, lon) = Tile::get_lat_lon(&path)?;
 
-        let f_len = file.metadata().map_err(|_| Error::Filesize)?.len();
-        let res = Resolution::try_from(f_len).map_err(|_| Error::Filesize)?;
+        let (res, elevation_data) =
+            Self::parse_file(&mut file, options).map_err(|e| e.in_file(&path))?;
         // eprintln!("resolution: {res:?}");
 
+        Ok(Tile::new(lat, lon, res, elevation_data))
+    }
+
+    /// create a [`Tile`] at `lat`, `lon` from the content of an `.hgt` file, eg. fetched over the network
+    /// the [`Resolution`] is inferred from the length of `bytes`,
+    /// gzip or zip compressed content is recognized and decompressed, as by [`Tile::from_file()`]
+    ///
+    /// # Errors
+    /// - [`Error::Filesize`] if the length of `bytes` doesn't match any [`Resolution`]
+    /// - [`Error::ParseLatLong`] if `lat`, `lon` isn't a valid south-west corner of a tile
+    /// - [`Error::Archive`] if it's compressed, but the feature needed is disabled, or it can't be decompressed
+    pub fn from_bytes(bytes: &[u8], lat: i8, lon: i16) -> Result<Tile, Error> {
+        let (res, elevation_data) = Self::parse_content(
+            io::Cursor::new(bytes),
+            bytes.len() as u64,
+            ParseOptions::default(),
+        )?;
+
+        Tile::try_new(lat, lon, res, elevation_data)
+    }
+    /// create a [`Tile`] from the content of an `.hgt` file, eg. embedded with `include_bytes!`
+    /// latitude and longitude are parsed from `name` as from a filename, see [`Tile::get_lat_lon()`],
+    /// the [`Resolution`] is inferred from the length of `bytes`
+    pub fn from_bytes_named(bytes: &[u8], name: &str) -> Result<Tile, Error> {
+        let (lat, lon) = Tile::get_lat_lon(name)?;
+        Tile::from_bytes(bytes, lat, lon)
+    }
+    /// same as [`Tile::from_file()`], but parse as `res`, instead of inferring it from the file's metadata
+    /// fails with [`Error::Filesize`] if the file doesn't contain exactly the samples of `res`
+    pub fn from_file_with_resolution<P: AsRef<Path>>(
+        path: P,
+        res: Resolution,
+    ) -> Result<Tile, Error> {
+        let mut file = File::open(&path).map_err(|e| Error::open(&path, e))?;
+        let (lat, lon) = Tile::get_lat_lon(&path)?;
+
+        let elevation_data = Self::parse_hgt(&mut file, res).map_err(|e| {
+            match e.kind() {
+                io::ErrorKind::UnexpectedEof => Error::filesize(),
+                _ => Error::read(e),
+            }
+            .in_file(&path)
+        })?;
+        if file
+            .read(&mut [0])
+            .map_err(|e| Error::read(e).in_file(&path))?
+            != 0
+        {
+            return Err(Error::filesize().in_file(&path));
+        }
+
+        Ok(Tile::new(lat, lon, res, elevation_data))
+    }
+
+    /// read the first `.hgt` file of the zip archive at `path`, as distributed by NASA, eg. `N44E015.SRTMGL1.hgt.zip`
+    /// latitude and longitude are taken from the name of the archive, see [`Tile::get_lat_lon()`]
+    ///
+    /// # Errors
+    /// [`Error::Archive`] if it isn't a zip archive, or there's no `.hgt` file in it
+    #[cfg(feature = "zip")]
+    pub fn from_zip_file<P: AsRef<Path>>(path: P) -> Result<Tile, Error> {
+        let file = File::open(&path).map_err(|e| Error::open(&path, e))?;
+        let (lat, lon) = Tile::get_lat_lon(&path)?;
+        let (res, elevation_data) =
+            Self::parse_zip(file, ParseOptions::default()).map_err(|e| e.in_file(&path))?;
+
+        Ok(Tile::new(lat, lon, res, elevation_data))
+    }
+
+    /// same as [`Tile::from_file()`], but opens the file asynchronously, reading and parsing happen on a blocking thread,
+    /// streamed as by [`Tile::from_file()`], without buffering the whole file first
+    #[cfg(feature = "tokio")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<Tile, Error> {
         let (lat, lon) = Tile::get_lat_lon(&path)?;
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| Error::open(&path, e))?
+            .into_std()
+            .await;
 
-        let elevation_data = Self::parse_hgt(file, res).map_err(|_| Error::Read)?;
+        let (res, elevation_data) = tokio::task::spawn_blocking(move || {
+            Self::parse_file(&mut file, ParseOptions::default())
+        })
+        .await
+        .map_err(|e| Error::read(io::Error::other(e)))
+        .and_then(|parsed| parsed)
+        .map_err(|e| e.in_file(&path))?;
 
         Ok(Tile::new(lat, lon, res, elevation_data))
     }
 
-    /// the maximum height that this [`Tile`] contains
-    pub fn max_height(&self) -> i16 {
-        *self.data.iter().max().unwrap_or(&0)
+    /// download an srtm: `.hgt` file from `url`, and create a [`Tile`] if possible, see [`Tile::from_bytes()`]
+    /// latitude and longitude are taken from the filename at the end of `url`, redirects are followed
+    /// compressed responses, eg. `.hgt.gz` are decompressed, a response longer than any `.hgt` file is rejected
+    ///
+    /// # Errors
+    /// - [`Error::Http`] if the request fails or the response isn't successful
+    /// - [`Error::Filesize`] if the response is too long, or its content doesn't match any [`Resolution`]
+    #[cfg(feature = "http")]
+    pub fn from_url(url: &str) -> Result<Tile, Error> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let (lat, lon) = Tile::get_lat_lon(path.rsplit('/').next().unwrap_or(path))?;
+        let response = ureq::get(url).call().map_err(|e| match e {
+            ureq::Error::Status(status, _) => Error::Http(Some(status)),
+            ureq::Error::Transport(_) => Error::Http(None),
+        })?;
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_HGT_LEN + 1)
+            .read_to_end(&mut bytes)
+            .map_err(Error::read)?;
+        if bytes.len() as u64 > MAX_HGT_LEN {
+            return Err(Error::filesize());
+        }
+
+        Tile::from_bytes(&bytes, lat, lon)
+    }
+
+    /// whether elevations of `0` are treated as voids, see [`Tile::with_zero_as_void()`]
+    pub fn zero_is_void(&self) -> bool {
+        self.zero_is_void
     }
-    /// the minimum height that this [`Tile`] contains
-    pub fn min_height(&self) -> i16 {
-        *self.data.iter().min().unwrap_or(&0)
+    /// whether `elev` is considered missing data in this [`Tile`]
+    /// see [`is_void()`] and [`Tile::with_zero_as_void()`]
+    pub fn treats_as_void(&self, elev: i16) -> bool {
+        is_void_with(elev, self.zero_is_void)
+    }
+
+    /// the identity of this [`Tile`]
+    pub fn id(&self) -> TileId {
+        TileId(self.latitude, self.longitude, self.resolution)
+    }
+
+    /// the maximum height that this [`Tile`] contains, voids excluded
+    /// `None` if it doesn't contain any valid elevation
+    pub fn max_height(&self) -> Option<i16> {
+        self.valid_elevations().max()
+    }
+    /// the minimum height that this [`Tile`] contains, voids excluded
+    /// `None` if it doesn't contain any valid elevation
+    pub fn min_height(&self) -> Option<i16> {
+        self.valid_elevations().min()
+    }
+    /// the minimum and maximum height that this [`Tile`] contains, voids excluded, in a single pass
+    /// `None` if it doesn't contain any valid elevation
+    pub fn elevation_range(&self) -> Option<(i16, i16)> {
+        self.valid_elevations()
+            .fold(None, |range, elev| match range {
+                None => Some((elev, elev)),
+                Some((min, max)) => Some((min.min(elev), max.max(elev))),
+            })
+    }
+    /// the mean of all valid elevations of this [`Tile`]
+    /// `None` if it doesn't contain any valid elevation
+    pub fn mean_elevation(&self) -> Option<f64> {
+        let (sum, count) = self
+            .valid_elevations()
+            .fold((0i64, 0usize), |(sum, count), elev| {
+                (sum + elev as i64, count + 1)
+            });
+        (count > 0).then(|| sum as f64 / count as f64)
+    }
+    /// the mean of the valid elevations inside the box between the `sw` and `ne` corners,
+    /// as selected by [`Tile::pixel_bounds()`]
+    /// `None` if the box doesn't contain any valid elevation of this [`Tile`]
+    pub fn mean_in(&self, sw: Coord, ne: Coord) -> Option<f64> {
+        let (top, left, bottom, right) = self.pixel_bounds(sw, ne)?;
+        let extent = self.resolution.extent();
+        let (sum, count) = (top..=bottom)
+            .flat_map(|row| &self.data[row * extent + left..=row * extent + right])
+            .filter(|elev| !self.treats_as_void(**elev))
+            .fold((0i64, 0usize), |(sum, count), elev| {
+                (sum + *elev as i64, count + 1)
+            });
+        (count > 0).then(|| sum as f64 / count as f64)
+    }
+    /// the ratio of voids among all elevations of this [`Tile`], in `0..=1`
+    pub fn void_ratio(&self) -> f64 {
+        if self.data.is_empty() {
+            return 1.;
+        }
+        let voids = self.data.len() - self.valid_elevations().count();
+        voids as f64 / self.data.len() as f64
+    }
+    /// the number of valid elevations of this [`Tile`] higher than `threshold`
+    pub fn count_above(&self, threshold: i16) -> usize {
+        self.valid_elevations()
+            .filter(|elev| *elev > threshold)
+            .count()
+    }
+    /// the number of valid elevations of this [`Tile`] lower than `threshold`
+    pub fn count_below(&self, threshold: i16) -> usize {
+        self.valid_elevations()
+            .filter(|elev| *elev < threshold)
+            .count()
+    }
+    /// whether this [`Tile`] contains nothing but voids
+    pub fn is_all_void(&self) -> bool {
+        self.data.iter().all(|elev| self.treats_as_void(*elev))
+    }
+
+    /// whether `coord` falls into this [`Tile`], see [`Coord::tile_key()`]
+    /// never for NaN, or a `coord` beyond ±90° latitude or ±180° longitude
+    pub fn contains(&self, coord: impl Into<Coord>) -> bool {
+        let coord = coord.into();
+        Coord::opt_new(coord.lat, coord.lon).is_some()
+            && coord.tile_key() == (self.latitude, self.longitude)
+    }
+    /// look up the elevation of `coord`, telling apart voids and coordinates outside of this [`Tile`]
+    /// unlike [`Tile::get()`], it never panics or prints warnings
+    pub fn lookup(&self, coord: impl Into<Coord>) -> Lookup {
+        let coord = coord.into();
+        if !self.contains(coord) {
+            return Lookup::OutsideTile;
+        }
+        let (row, col) = self.get_offset(coord);
+        match self.data.get(row * self.resolution.extent() + col) {
+            None => Lookup::OutsideTile,
+            Some(elev) if self.treats_as_void(*elev) => Lookup::Void,
+            Some(elev) => Lookup::Valid(*elev),
+        }
+    }
+
+    /// whether `coord` is inside this [`Tile`], and its sample isn't a void
+    /// unlike [`Tile::contains()`], it checks the data, without warnings
+    pub fn has_valid_data_at(&self, coord: impl Into<Coord>) -> bool {
+        matches!(self.lookup(coord), Lookup::Valid(_))
+    }
+
+    /// the sample stored for `coord`, voids included as they are, eg. `Some(-9999)`
+    /// `None` only if this [`Tile`] doesn't contain `coord`, never prints warnings
+    pub fn raw_at(&self, coord: impl Into<Coord>) -> Option<i16> {
+        let coord = coord.into();
+        if !self.contains(coord) {
+            return None;
+        }
+        let (row, col) = self.get_offset(coord);
+        self.data.get(row * self.resolution.extent() + col).copied()
+    }
+
+    /// get the elevation of this `coord` from this [`Tile`]
+    /// same as [`Tile::get()`], but returns the elevation by value
+    ///
+    /// # Panics
+    /// If this [`Tile`] doesn't contain `coord`'s elevation
+    pub fn elevation(&self, coord: impl Into<Coord>) -> Option<i16> {
+        self.get(coord).copied()
     }
 
     /// get the elevation of this `coord` from this [`Tile`]
+    /// prefer [`Tile::elevation()`], as `i16` is cheap to copy
     ///
     /// # Panics
     /// If this [`Tile`] doesn't contain `coord`'s elevation
@@ -64,7 +459,7 @@ impl Tile {
     pub fn get(&self, coord: impl Into<Coord>) -> Option<&i16> {
         let coord: Coord = coord.into();
         let offset = self.get_offset(coord);
-        let (lat, lon) = coord.trunc();
+        let (lat, lon) = coord.tile_key();
         assert!(
             self.latitude <= lat,
             "hgt lat: {}, coord lat: {lat}",
@@ -76,51 +471,632 @@ impl Tile {
             self.longitude
         );
         let elev = self.get_at_offset(offset.1, offset.0);
-        if elev.is_some_and(|e| *e == -9999 || *e == i16::MIN) {
-            eprintln!(
-                "WARNING: in file {:?} {coord:?} doesn't contain a valid elevation: {elev:?}",
-                Coord::new(self.latitude, self.longitude).get_filename()
-            );
-            None
-        } else {
-            elev
+        match elev {
+            Some(e) if self.treats_as_void(*e) => {
+                // zeros are voids by choice, eg. the sea, no need to warn about them
+                if is_void(*e) {
+                    eprintln!(
+                        "WARNING: in file {:?} {coord:?} doesn't contain a valid elevation: {elev:?}",
+                        Coord::new(self.latitude, self.longitude).get_filename()
+                    );
+                }
+                None
+            }
+            _ => elev,
         }
     }
 
-    /// extract the heights from the `hgt` content
-    pub fn parse_hgt(mut reader: impl Read, res: Resolution) -> io::Result<Vec<i16>> {
-        let mut buffer = vec![0; res.total_len() * 2];
+    /// call `f` with the index and elevation of each of `coords`, in order
+    /// for many lookups in one [`Tile`], eg. to fill a buffer in one pass
+    ///
+    /// the elevation is `None` for voids and coords outside of this [`Tile`], without warnings or panics
+    pub fn for_coords<F: FnMut(usize, Option<i16>)>(&self, coords: &[Coord], mut f: F) {
+        for (i, coord) in coords.iter().enumerate() {
+            let elev = if self.contains(*coord) {
+                let (row, col) = self.get_offset(*coord);
+                self.get_at_offset(col, row)
+                    .copied()
+                    .filter(|elev| !self.treats_as_void(*elev))
+            } else {
+                None
+            };
+            f(i, elev);
+        }
+    }
+
+    /// same as [`Tile::elevation()`], but a `coord` that's outside of this [`Tile`] by at most [`CLAMP_TOLERANCE`]
+    /// is moved onto the nearest edge, instead of failing
+    /// `None` if `coord` is any further, or its elevation is a void
+    pub fn get_clamped(&self, coord: impl Into<Coord>) -> Option<i16> {
+        let coord = coord.into();
+        // position inside the tile, from the north-west corner, 0..=1 if inside
+        let from_north = self.latitude as f64 + 1. - coord.lat;
+        let from_west = coord.lon - self.longitude as f64;
+        let allowed = -CLAMP_TOLERANCE..=1. + CLAMP_TOLERANCE;
+        if !allowed.contains(&from_north) || !allowed.contains(&from_west) {
+            return None;
+        }
+        let cells = self.resolution.cells_per_side() as f64;
+        let row = (from_north.clamp(0., 1.) * cells).round() as usize;
+        let col = (from_west.clamp(0., 1.) * cells).round() as usize;
+        self.get_at_offset(col, row)
+            .copied()
+            .filter(|elev| !self.treats_as_void(*elev))
+    }
+
+    /// the difference of every sample of `self` and `other`: `self - other`, in row-major order, as [`Tile::data`]
+    /// the difference saturates at the limits of `i16`, where either sample is a void, it's [`VOID`]
+    ///
+    /// # Errors
+    /// [`Error::TileMismatch`] if `other` isn't of the same location and resolution
+    pub fn difference(&self, other: &Tile) -> Result<Vec<i16>, Error> {
+        if self.id() != other.id() || self.data.len() != other.data.len() {
+            return Err(Error::TileMismatch);
+        }
+        Ok(self
+            .data
+            .iter()
+            .zip(&other.data)
+            .map(|(a, b)| {
+                if self.treats_as_void(*a) || other.treats_as_void(*b) {
+                    VOID
+                } else {
+                    a.saturating_sub(*b)
+                }
+            })
+            .collect())
+    }
+
+    /// the geographic center of this [`Tile`]
+    pub fn center(&self) -> Coord {
+        Coord {
+            lat: self.latitude as f64 + 0.5,
+            lon: self.longitude as f64 + 0.5,
+        }
+    }
+    /// an overview of this [`Tile`]
+    pub fn summary(&self) -> TileSummary {
+        let range = self.elevation_range();
+        TileSummary {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            resolution: self.resolution,
+            center: self.center(),
+            min: range.map(|(min, _)| min),
+            max: range.map(|(_, max)| max),
+            void_ratio: self.void_ratio(),
+        }
+    }
+
+    /// the metadata of this [`Tile`] as a JSON object, everything but the elevations:
+    /// `lat`, `lon`, `resolution`, `extent`, `min`, `max`, `void_ratio` and `center`
+    /// `min` and `max` are `null` if there are only voids
+    #[cfg(feature = "serde")]
+    pub fn metadata_json(&self) -> String {
+        let summary = self.summary();
+        serde_json::json!({
+            "lat": summary.latitude,
+            "lon": summary.longitude,
+            "resolution": format!("{:?}", summary.resolution),
+            "extent": self.resolution.extent(),
+            "min": summary.min,
+            "max": summary.max,
+            "void_ratio": summary.void_ratio,
+            "center": { "lat": summary.center.lat, "lon": summary.center.lon },
+        })
+        .to_string()
+    }
+
+    /// the GDAL geotransform of this [`Tile`]: `[origin_x, pixel_width, 0, origin_y, 0, -pixel_height]`
+    ///
+    /// GDAL treats pixels as areas, with samples in their centers, so the origin is half a cell north-west
+    /// of the north-west corner of this [`Tile`], as GDAL itself reports for `.hgt` files
+    pub fn geotransform(&self) -> [f64; 6] {
+        let cell_size = self.resolution.cell_size_deg();
+        [
+            self.longitude as f64 - cell_size / 2.,
+            cell_size,
+            0.,
+            self.latitude as f64 + 1. + cell_size / 2.,
+            0.,
+            -cell_size,
+        ]
+    }
+
+    /// the northernmost row of samples, west to east, shared with the [`Tile`] to the north
+    pub fn north_edge(&self) -> &[i16] {
+        &self.data[..self.resolution.extent()]
+    }
+    /// the southernmost row of samples, west to east, shared with the [`Tile`] to the south
+    pub fn south_edge(&self) -> &[i16] {
+        &self.data[self.data.len() - self.resolution.extent()..]
+    }
+    /// the westernmost column of samples, north to south, shared with the [`Tile`] to the west
+    pub fn west_edge(&self) -> Vec<i16> {
+        self.column(0)
+    }
+    /// the easternmost column of samples, north to south, shared with the [`Tile`] to the east
+    pub fn east_edge(&self) -> Vec<i16> {
+        self.column(self.resolution.extent() - 1)
+    }
+
+    /// a transposed copy of `data`, for libraries expecting column-major (FORTRAN) order
+    ///
+    /// `data` is row-major from the north-west corner: `data[row * extent + col]`, rows going south.
+    /// the copy is column-major from the same corner: `[col * extent + row]`, so each column runs north to south,
+    /// and columns go from west to east
+    pub fn to_col_major(&self) -> Vec<i16> {
+        let extent = self.resolution.extent();
+        (0..extent).flat_map(|col| self.column(col)).collect()
+    }
+
+    /// the coordinate of the sample at `row`, `col`, counted from the north-west corner
+    pub fn coord_at(&self, row: usize, col: usize) -> Coord {
+        let cell_size = self.resolution.cell_size_deg();
+        Coord {
+            lat: self.latitude as f64 + 1. - row as f64 * cell_size,
+            lon: self.longitude as f64 + col as f64 * cell_size,
+        }
+    }
+    /// the valid elevations of this [`Tile`] with the coordinates of their samples, see [`Tile::coord_at()`]
+    /// north to south, west to east, voids are skipped
+    pub fn iter_valid(&self) -> impl Iterator<Item = (Coord, i16)> + '_ {
+        let extent = self.resolution.extent();
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, elev)| !self.treats_as_void(**elev))
+            .map(move |(i, elev)| (self.coord_at(i / extent, i % extent), *elev))
+    }
+    /// the rows of this [`Tile`] in parallel, north to south, each with the coordinate of its westernmost sample
+    /// the iterator runs on the pool it's consumed in, eg. on a custom one inside of [`rayon::ThreadPool::install()`]
+    #[cfg(feature = "rayon")]
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = (Coord, &[i16])> + '_ {
+        self.data
+            .par_chunks(self.resolution.extent())
+            .enumerate()
+            .map(|(row, samples)| (self.coord_at(row, 0), samples))
+    }
+    /// the samples along the straight line from `from` to `to` in the grid of this [`Tile`], one for each cell it crosses
+    /// walked with Bresenham's algorithm between the nearest samples of the ends, both included
+    ///
+    /// voids are `None`, the result is empty unless both ends are in this [`Tile`]
+    pub fn sample_line(&self, from: Coord, to: Coord) -> Vec<(Coord, Option<i16>)> {
+        if !self.contains(from) || !self.contains(to) {
+            return Vec::new();
+        }
+        let ((row, col), (to_row, to_col)) = (self.get_offset(from), self.get_offset(to));
+        let (mut row, mut col) = (row as isize, col as isize);
+        let (to_row, to_col) = (to_row as isize, to_col as isize);
+        let (d_row, d_col) = ((to_row - row).abs(), -(to_col - col).abs());
+        let (step_row, step_col) = ((to_row - row).signum(), (to_col - col).signum());
+        let mut error = d_row + d_col;
+        let mut samples = Vec::with_capacity(d_row.max(-d_col) as usize + 1);
+        loop {
+            let (r, c) = (row as usize, col as usize);
+            let elev = self
+                .get_at_offset(c, r)
+                .copied()
+                .filter(|elev| !self.treats_as_void(*elev));
+            samples.push((self.coord_at(r, c), elev));
+            if (row, col) == (to_row, to_col) {
+                break samples;
+            }
+            let doubled = 2 * error;
+            if doubled >= d_col {
+                error += d_col;
+                row += step_row;
+            }
+            if doubled <= d_row {
+                error += d_row;
+                col += step_col;
+            }
+        }
+    }
+    /// the coordinates of all voids in this [`Tile`], north to south, west to east
+    /// lazy, as there can be millions of them, eg. over the sea
+    pub fn void_coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        let extent = self.resolution.extent();
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, elev)| self.treats_as_void(**elev))
+            .map(move |(i, _)| self.coord_at(i / extent, i % extent))
+    }
+    /// the samples of this [`Tile`] inside the box between the `sw` and `ne` corners, edges included,
+    /// as `(top_row, left_col, bottom_row, right_col)`, all inclusive, clamped to this [`Tile`]
+    /// a box between two rows or columns of samples gets the one nearest to its middle
+    /// `None` if the box doesn't intersect this [`Tile`], or `sw` isn't south-west of `ne`
+    pub fn pixel_bounds(&self, sw: Coord, ne: Coord) -> Option<(usize, usize, usize, usize)> {
+        let cells = self.resolution.cells_per_side() as f64;
+        let (north, west) = (self.latitude as f64 + 1., self.longitude as f64);
+        let inside = sw.lat <= ne.lat
+            && sw.lon <= ne.lon
+            && sw.lat <= north
+            && ne.lat >= north - 1.
+            && sw.lon <= west + 1.
+            && ne.lon >= west;
+        // false for NaN as well
+        if !inside {
+            return None;
+        }
+        let clamp = |i: f64| i.clamp(0., cells);
+        // the samples from the `first` to the `last` offset, or the one nearest to their middle if there's none between
+        let span = |first: f64, last: f64| {
+            let (from, to) = (clamp(first.ceil()), clamp(last.floor()));
+            if from > to {
+                let nearest = clamp(((first + last) / 2.).round());
+                (nearest, nearest)
+            } else {
+                (from, to)
+            }
+        };
+        let (top, bottom) = span((north - ne.lat) * cells, (north - sw.lat) * cells);
+        let (left, right) = span((sw.lon - west) * cells, (ne.lon - west) * cells);
+        Some((top as usize, left as usize, bottom as usize, right as usize))
+    }
+    /// the south-west and north-east corners of the area containing valid elevations
+    /// `None` if this [`Tile`] contains nothing but voids
+    pub fn valid_bounds(&self) -> Option<(Coord, Coord)> {
+        let extent = self.resolution.extent();
+        let (mut top, mut left) = (usize::MAX, usize::MAX);
+        let (mut bottom, mut right) = (0, 0);
+        for (i, _) in self
+            .data
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| !self.treats_as_void(**e))
+        {
+            let (row, col) = (i / extent, i % extent);
+            top = top.min(row);
+            bottom = bottom.max(row);
+            left = left.min(col);
+            right = right.max(col);
+        }
+        if top == usize::MAX {
+            return None;
+        }
+        Some((self.coord_at(bottom, left), self.coord_at(top, right)))
+    }
+
+    /// apply `f` to every elevation of this [`Tile`] in place, voids are left untouched
+    pub fn map_elevations(&mut self, f: impl Fn(i16) -> i16) {
+        let zero_is_void = self.zero_is_void;
+        self.data
+            .iter_mut()
+            .filter(|elev| !is_void_with(**elev, zero_is_void))
+            .for_each(|elev| *elev = f(*elev));
+    }
+    /// clamp every elevation of this [`Tile`] into `min..=max` in place, eg. to cap spikes before rendering
+    /// voids are left untouched
+    ///
+    /// # Panics
+    /// if `min > max`
+    pub fn clamp_elevations(&mut self, min: i16, max: i16) {
+        assert!(min <= max, "min: {min} is greater than max: {max}");
+        self.map_elevations(|elev| elev.clamp(min, max));
+    }
+    /// add `meters` to every elevation of this [`Tile`] in place, eg. for a vertical datum shift
+    /// clamps at the limits of `i16` instead of overflowing, voids are left untouched
+    ///
+    /// an elevation never becomes a void: the lowest it goes is `i16::MIN + 1`,
+    /// and one that would land on a void marker, see [`Tile::treats_as_void()`], stays a meter short of it
+    pub fn apply_offset(&mut self, meters: i16) {
+        let zero_is_void = self.zero_is_void;
+        self.map_elevations(|elev| offset_valid(elev, meters, zero_is_void));
+    }
+    /// add the matching cell of `grid` to every elevation of this [`Tile`] in place, eg. for sensor-bias correction
+    /// `grid` is in the same order as [`Tile::data`], clamps as [`Tile::apply_offset()`], voids are left untouched
+    ///
+    /// # Panics
+    /// if `grid` isn't as long as [`Tile::data`]
+    pub fn apply_correction_grid(&mut self, grid: &[i16]) {
+        assert_eq!(
+            grid.len(),
+            self.data.len(),
+            "correction grid has to match the size of the tile"
+        );
+        let zero_is_void = self.zero_is_void;
+        self.data
+            .iter_mut()
+            .zip(grid)
+            .filter(|(elev, _)| !is_void_with(**elev, zero_is_void))
+            .for_each(|(elev, correction)| *elev = offset_valid(*elev, *correction, zero_is_void));
+    }
+    /// same as [`Tile::map_elevations()`], but returns a new [`Tile`] instead
+    pub fn mapped(&self, f: impl Fn(i16) -> i16) -> Tile {
+        let mut tile = self.clone();
+        tile.map_elevations(f);
+        tile
+    }
+
+    /// where `coord`'s elevation is stored in the `.hgt` file of this [`Tile`], in bytes
+    /// `None` if this [`Tile`] doesn't contain `coord`
+    pub fn byte_offset(&self, coord: impl Into<Coord>) -> Option<u64> {
+        let coord = coord.into();
+        if !self.contains(coord) {
+            return None;
+        }
+        Self::byte_offset_in(self.resolution, coord)
+    }
+    /// where `coord`'s elevation is stored in its `.hgt` file of [`Resolution`] `res`, in bytes
+    /// `None` if `coord` is NaN, or beyond ±90° latitude or ±180° longitude, so outside of any file
+    pub fn byte_offset_in(res: Resolution, coord: impl Into<Coord>) -> Option<u64> {
+        let (row, col) = Self::fractional_offset_in(res, coord.into());
+        let inside = 0. ..=res.cells_per_side() as f64;
+        if !inside.contains(&row) || !inside.contains(&col) {
+            return None;
+        }
+        let (row, col) = (row.round() as usize, col.round() as usize);
+        Some(2 * (row * res.extent() + col) as u64)
+    }
+    /// read a single elevation at `offset` bytes, as returned by [`Tile::byte_offset()`]
+    pub fn from_reader_at(mut reader: impl Read + Seek, offset: u64) -> io::Result<i16> {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut buffer = [0; 2];
         reader.read_exact(&mut buffer)?;
-        let mut elevations = Vec::with_capacity(res.total_len());
-        for chunk in buffer.chunks_exact(2) {
-            let value = i16::from_be_bytes([chunk[0], chunk[1]]);
-            elevations.push(value);
+        Ok(i16::from_be_bytes(buffer))
+    }
+
+    /// replace all voids with `value`, eg. `0` for sea level, genuine elevations are left untouched
+    pub fn set_voids_to(&mut self, value: i16) {
+        let zero_is_void = self.zero_is_void;
+        self.data
+            .iter_mut()
+            .filter(|elev| is_void_with(**elev, zero_is_void))
+            .for_each(|elev| *elev = value);
+    }
+
+    /// a hash of the location, [`Resolution`] and elevations of this [`Tile`], eg. to invalidate cached derived data
+    /// 64-bit FNV-1a, the same on every run and platform, unlike [`std::hash::Hash`]
+    /// [`Tile::zero_is_void()`] isn't included, it's not a property of the data
+    pub fn content_hash(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let header = self
+            .latitude
+            .to_be_bytes()
+            .into_iter()
+            .chain(self.longitude.to_be_bytes())
+            .chain((self.resolution.extent() as u32).to_be_bytes());
+        header
+            .chain(self.data.iter().flat_map(|elev| elev.to_be_bytes()))
+            .fold(OFFSET_BASIS, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(PRIME)
+            })
+    }
+    /// the content of the `.hgt` file of this [`Tile`]: big-endian elevations
+    pub fn as_be_bytes(&self) -> Vec<u8> {
+        self.data
+            .iter()
+            .flat_map(|elev| elev.to_be_bytes())
+            .collect()
+    }
+    /// write the content of the `.hgt` file of this [`Tile`] to `writer`, a row at a time
+    pub fn write_be_bytes(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut row_buffer = Vec::with_capacity(self.resolution.extent() * 2);
+        for row in self.data.chunks(self.resolution.extent()) {
+            row_buffer.clear();
+            row_buffer.extend(row.iter().flat_map(|elev| elev.to_be_bytes()));
+            writer.write_all(&row_buffer)?;
+        }
+        Ok(())
+    }
+
+    /// write `lat,lon,elevation` rows of all samples of this [`Tile`] to `w` as CSV, after a header
+    /// north to south, west to east, streamed a row at a time, optionally skipping voids
+    pub fn write_csv<W: Write>(&self, w: W, skip_voids: bool) -> io::Result<()> {
+        let mut w = io::BufWriter::new(w);
+        writeln!(w, "lat,lon,elevation")?;
+        let extent = self.resolution.extent();
+        for (i, elev) in self.data.iter().enumerate() {
+            if skip_voids && self.treats_as_void(*elev) {
+                continue;
+            }
+            let coord = self.coord_at(i / extent, i % extent);
+            writeln!(w, "{:.7},{:.7},{elev}", coord.lat, coord.lon)?;
+        }
+        w.flush()
+    }
+
+    /// extract the heights from the `hgt` content
+    /// `reader` is buffered a few rows at a time and decoded row by row, so only the elevations are kept in memory as a whole
+    /// and sources returning little data per read, eg. decompressors, aren't asked for every few bytes
+    pub fn parse_hgt(reader: impl Read, res: Resolution) -> io::Result<Vec<i16>> {
+        Self::parse_samples(reader, res.extent(), res.extent())
+    }
+    /// same as [`Tile::parse_hgt()`], but parse according to `options`
+    pub fn parse_hgt_with(
+        reader: impl Read,
+        res: Resolution,
+        options: ParseOptions,
+    ) -> Result<Vec<i16>, Error> {
+        let elevations = Self::parse_hgt(reader, res).map_err(Error::read)?;
+        if options.validate {
+            let implausible = elevations
+                .iter()
+                .filter(|elev| !is_void(**elev) && !PLAUSIBLE_ELEVATIONS.contains(elev))
+                .count();
+            if implausible as f64 > elevations.len() as f64 * MAX_IMPLAUSIBLE_RATIO {
+                return Err(Error::ImplausibleData);
+            }
         }
         Ok(elevations)
     }
 
     /// extract the latitude and longitude from a filepath
+    /// only the part of the filename before the first `.` is considered,
+    /// so `N35E138`, `N35E138.hgt` and `N35E138.hgt.gz` are all fine
+    /// latitude and longitude can have 1 to 3 digits, with or without zero-padding: `N5E5`, `N05E005`
     /// let ne = Path::new("N35E138.hgt");
     /// assert_eq!(Tile::get_lat_lon(ne).unwrap(), (35, 138));
+    ///
+    /// # Errors
+    /// - [`Error::FilenameLength`] if the name ends early, or has characters after the longitude
+    /// - [`Error::FilenameHemisphere`] if a hemisphere letter is wrong, eg. `N44X015`
+    /// - [`Error::FilenameDigits`] if a hemisphere isn't followed by 1 to 3 digits, eg. `NX4E015`
+    /// - [`Error::ParseLatLong`] if it's well-formed, but not the south-west corner of a tile, eg. `N91E015` or `N90E000`
     pub fn get_lat_lon(path: impl AsRef<Path>) -> Result<(i8, i16), Error> {
-        let stem = path.as_ref().file_stem().ok_or(Error::ParseLatLong)?;
-        let desc = stem.to_str().ok_or(Error::ParseLatLong)?;
-        if desc.len() != 7 {
+        let name = path.as_ref().file_name().unwrap_or_default();
+        let name = name.to_string_lossy();
+        let desc = name.split('.').next().unwrap_or_default();
+
+        // a hemisphere letter, followed by 1 to 3 digits, returns the signed number and the rest
+        fn parse_part(part: &str, positive: char, negative: char) -> Result<(i16, &str), Error> {
+            let mut chars = part.chars();
+            let sign = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                None => return Err(Error::FilenameLength),
+                Some(c) if c == positive => 1,
+                Some(c) if c == negative => -1,
+                _ => return Err(Error::FilenameHemisphere),
+            };
+            let rest = chars.as_str();
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if !(1..=3).contains(&digits) {
+                return Err(Error::FilenameDigits);
+            }
+            let value: i16 = rest[..digits].parse().map_err(|_| Error::FilenameDigits)?;
+            Ok((sign * value, &rest[digits..]))
+        }
+        let (lat, rest) = parse_part(desc, 'N', 'S')?;
+        let (lon, rest) = parse_part(rest, 'E', 'W')?;
+        if !rest.is_empty() {
+            return Err(Error::FilenameLength);
+        }
+        // the south-west corner of a tile, so neither the north pole nor the antimeridian to the east
+        if !(-90..=89).contains(&lat) || !(-180..=179).contains(&lon) {
             return Err(Error::ParseLatLong);
         }
-
-        let get_char = |n| desc.chars().nth(n).ok_or(Error::ParseLatLong);
-        let lat_sign = if get_char(0)? == 'N' { 1 } else { -1 };
-        let lat: i8 = desc[1..3].parse().map_err(|_| Error::ParseLatLong)?;
-
-        let lon_sign = if get_char(3)? == 'E' { 1 } else { -1 };
-        let lon: i16 = desc[4..7].parse().map_err(|_| Error::ParseLatLong)?;
-        Ok((lat_sign * lat, lon_sign * lon))
+        Ok((lat as i8, lon))
     }
 }
 
 // impl for non-pub fn-s
 impl Tile {
+    /// parse the content of `file`, plain `.hgt` if its size matches a [`Resolution`], compressed otherwise
+    fn parse_file(file: &mut File, options: ParseOptions) -> Result<(Resolution, Vec<i16>), Error> {
+        let f_len = file.metadata().map_err(Error::read)?.len();
+        Self::parse_content(file, f_len, options)
+    }
+    /// parse `len` bytes of `.hgt` content from `reader`, raw if `len` matches a [`Resolution`], compressed otherwise
+    fn parse_content<R: Read + Seek>(
+        reader: R,
+        len: u64,
+        options: ParseOptions,
+    ) -> Result<(Resolution, Vec<i16>), Error> {
+        match Resolution::try_from(len) {
+            Ok(res) => Ok((res, Self::parse_hgt_with(reader, res, options)?)),
+            Err(()) => Self::parse_compressed(reader, options),
+        }
+    }
+    /// decompress and parse the content of `reader`, recognized by its first bytes as gzip or zip
+    /// the [`Resolution`] is inferred from the length of the decompressed `.hgt` content
+    fn parse_compressed<R: Read + Seek>(
+        mut reader: R,
+        options: ParseOptions,
+    ) -> Result<(Resolution, Vec<i16>), Error> {
+        let mut magic = [0; 4];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| Error::filesize())?;
+        reader.seek(SeekFrom::Start(0)).map_err(Error::read)?;
+        match magic {
+            [0x1f, 0x8b, ..] => Self::parse_gzip(reader, options),
+            [b'P', b'K', 3, 4] => Self::parse_zip(reader, options),
+            _ => Err(Error::filesize()),
+        }
+    }
+    /// parse the gzip compressed `.hgt` content of `reader`, streaming it without buffering the decompressed bytes
+    /// the [`Resolution`] is inferred from the length of the content stored in the gzip trailer
+    #[cfg(feature = "gzip")]
+    fn parse_gzip(
+        mut reader: impl Read + Seek,
+        options: ParseOptions,
+    ) -> Result<(Resolution, Vec<i16>), Error> {
+        // ISIZE: the length of the decompressed content modulo 2^32, which every `.hgt` file fits into
+        let mut isize = [0; 4];
+        reader
+            .seek(SeekFrom::End(-4))
+            .and_then(|_| reader.read_exact(&mut isize))
+            .and_then(|_| reader.seek(SeekFrom::Start(0)))
+            .map_err(|_| Error::Archive)?;
+        let res = Resolution::try_from(u32::from_le_bytes(isize) as u64)
+            .map_err(|_| Error::filesize())?;
+
+        let mut decoder = flate2::read::GzDecoder::new(reader);
+        let elevations = Self::parse_hgt_with(&mut decoder, res, options).map_err(|e| match e {
+            // corrupt, or shorter than its trailer claims
+            Error::Read { .. } => Error::Archive,
+            e => e,
+        })?;
+        // reading to the end checks the trailer
+        match decoder.read(&mut [0]) {
+            Ok(0) => Ok((res, elevations)),
+            Ok(_) => Err(Error::filesize()),
+            Err(_) => Err(Error::Archive),
+        }
+    }
+    #[cfg(not(feature = "gzip"))]
+    fn parse_gzip(_: impl Read + Seek, _: ParseOptions) -> Result<(Resolution, Vec<i16>), Error> {
+        Err(Error::Archive)
+    }
+    /// parse the first `.hgt` file of the zip archive `reader`
+    #[cfg(feature = "zip")]
+    fn parse_zip(
+        reader: impl Read + Seek,
+        options: ParseOptions,
+    ) -> Result<(Resolution, Vec<i16>), Error> {
+        let mut archive = zip::ZipArchive::new(reader).map_err(|_| Error::Archive)?;
+        let index = (0..archive.len())
+            .find(|i| {
+                archive
+                    .name_for_index(*i)
+                    .is_some_and(|name| name.to_ascii_lowercase().ends_with(".hgt"))
+            })
+            .ok_or(Error::Archive)?;
+        let entry = archive.by_index(index).map_err(|_| Error::Archive)?;
+        let res = Resolution::try_from(entry.size()).map_err(|_| Error::filesize())?;
+
+        Ok((res, Self::parse_hgt_with(entry, res, options)?))
+    }
+    #[cfg(not(feature = "zip"))]
+    fn parse_zip(_: impl Read + Seek, _: ParseOptions) -> Result<(Resolution, Vec<i16>), Error> {
+        Err(Error::Archive)
+    }
+    /// [`Tile::parse_hgt()`] for `rows` * `cols` samples, eg. of a [`crate::PartialTile`]
+    pub(crate) fn parse_samples(
+        reader: impl Read,
+        rows: usize,
+        cols: usize,
+    ) -> io::Result<Vec<i16>> {
+        let row_len = cols * 2;
+        let mut reader = BufReader::with_capacity(PARSE_BUFFER_ROWS * row_len, reader);
+        let mut elevations = Vec::with_capacity(rows * cols);
+        let mut row = vec![0; row_len];
+        for _ in 0..rows {
+            reader.read_exact(&mut row)?;
+            byte_swap::extend_from_be(&mut elevations, &row);
+        }
+        Ok(elevations)
+    }
+    /// all samples in column `col`, north to south
+    fn column(&self, col: usize) -> Vec<i16> {
+        self.data
+            .iter()
+            .skip(col)
+            .step_by(self.resolution.extent())
+            .copied()
+            .collect()
+    }
+    /// all elevations of `self`, voids skipped
+    fn valid_elevations(&self) -> impl Iterator<Item = i16> + '_ {
+        self.data
+            .iter()
+            .copied()
+            .filter(|elev| !self.treats_as_void(*elev))
+    }
     /// index `self` as if it was a matrix
     fn get_at_offset(&self, x: usize, y: usize) -> Option<&i16> {
         self.data.get(self.idx(x, y))
@@ -137,21 +1113,136 @@ impl Tile {
         );
         y * self.resolution.extent() + x
     }
-    /// get lower-left corner's latitude and longitude
+    /// get the upper-left, north-west corner's latitude and longitude of the tile containing `coord`
     /// it's needed for [`Tile::get_offset()`]
-    fn get_origin(&self, coord: Coord) -> Coord {
-        let lat = coord.lat.trunc() + 1.; // The latitude of the lower-left corner of the tile
-        let lon = coord.lon.trunc(); // The longitude of the lower-left corner of the tile
-        Coord { lat, lon }
+    fn get_origin(coord: Coord) -> Coord {
+        let (lat, lon) = coord.tile_key();
+        // the north-west corner: one degree north of the south-west one
+        Coord {
+            lat: lat as f64 + 1.,
+            lon: lon as f64,
+        }
     }
     /// calculate where this `coord` is located in this [`Tile`]
     fn get_offset(&self, coord: Coord) -> (usize, usize) {
-        let origin = self.get_origin(coord);
+        Self::offset_in(self.resolution, coord)
+    }
+    /// calculate where this `coord` is located in a [`Tile`] of [`Resolution`] `res`
+    /// the nearest sample is chosen, a degree spans [`Resolution::cells_per_side()`] intervals between samples
+    fn offset_in(res: Resolution, coord: Coord) -> (usize, usize) {
+        let (row, col) = Self::fractional_offset_in(res, coord);
+        (row.round() as usize, col.round() as usize)
+    }
+    /// the row and column of `coord` in a [`Tile`] of [`Resolution`] `res`, unrounded and unchecked:
+    /// negative, past the last sample or NaN for coordinates outside of the tile [`Coord::tile_key()`] clamps them to
+    fn fractional_offset_in(res: Resolution, coord: Coord) -> (f64, f64) {
+        let origin = Self::get_origin(coord);
         // eprintln!("origin: ({}, {})", origin.0, origin.1);
-        let extent = self.resolution.extent() as f64;
+        let cells = res.cells_per_side() as f64;
 
-        let row = ((origin.lat - coord.lat) * extent) as usize;
-        let col = ((coord.lon - origin.lon) * extent) as usize;
+        let row = (origin.lat - coord.lat) * cells;
+        let col = (coord.lon - origin.lon) * cells;
         (row, col)
     }
 }
+
+/// builds a [`Tile`] row by row, from north to south, eg. while decoding a custom source
+///
+/// # Usage
+///
+/// ```rust
+/// use srtm_reader::{Resolution, TileBuilder};
+///
+/// let res = Resolution::SRTM3;
+/// let mut builder = TileBuilder::new(44, 15, res);
+/// for row in 0..res.extent() {
+///     builder.push_row(&vec![row as i16; res.extent()]).unwrap();
+/// }
+/// let tile = builder.build().unwrap();
+/// assert_eq!(tile.elevation((44.9999, 15.5)), Some(0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileBuilder {
+    latitude: i8,
+    longitude: i16,
+    resolution: Resolution,
+    data: Vec<i16>,
+}
+
+impl TileBuilder {
+    /// start an empty [`Tile`] with its south-west corner at `lat`, `lon`
+    pub fn new(lat: i8, lon: i16, res: Resolution) -> Self {
+        Self {
+            latitude: lat,
+            longitude: lon,
+            resolution: res,
+            data: Vec::with_capacity(res.total_len()),
+        }
+    }
+    /// append the next row, west to east
+    ///
+    /// # Errors
+    /// [`Error::Filesize`] if `row` isn't [`Resolution::extent()`] long, or all rows are pushed already
+    pub fn push_row(&mut self, row: &[i16]) -> Result<(), Error> {
+        if row.len() != self.resolution.extent() || self.rows() == self.resolution.extent() {
+            return Err(Error::filesize());
+        }
+        self.data.extend_from_slice(row);
+        Ok(())
+    }
+    /// the number of rows pushed so far
+    pub fn rows(&self) -> usize {
+        self.data.len() / self.resolution.extent()
+    }
+    /// finish the [`Tile`], same as [`Tile::try_new()`]
+    ///
+    /// # Errors
+    /// - [`Error::Filesize`] if not all rows are pushed
+    /// - [`Error::ParseLatLong`] if the corner isn't valid
+    pub fn build(self) -> Result<Tile, Error> {
+        Tile::try_new(self.latitude, self.longitude, self.resolution, self.data)
+    }
+}
+
+/// (de)serializing elevations as big-endian bytes, which formats like bincode store as they are
+#[cfg(feature = "serde")]
+mod be_bytes {
+    use serde::{de, Deserializer, Serializer};
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(data: &[i16], serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes: Vec<u8> = data.iter().flat_map(|elev| elev.to_be_bytes()).collect();
+        serializer.serialize_bytes(&bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<i16>, D::Error> {
+        deserializer.deserialize_bytes(BeBytes)
+    }
+
+    struct BeBytes;
+
+    impl<'de> de::Visitor<'de> for BeBytes {
+        type Value = Vec<i16>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an even number of bytes")
+        }
+        fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+            if !bytes.len().is_multiple_of(2) {
+                return Err(E::invalid_length(bytes.len(), &self));
+            }
+            Ok(bytes
+                .chunks_exact(2)
+                .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+                .collect())
+        }
+        // self-describing formats like JSON hand over a sequence of numbers instead
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            self.visit_bytes(&bytes)
+        }
+    }
+}
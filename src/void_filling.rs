@@ -0,0 +1,73 @@
+//! filling the voids of a [`Tile`] from the valid samples around them
+
+use super::{tiles::round_valid, Tile};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+impl Tile {
+    /// replace each void with the mean of the valid samples within `radius` rows and columns of it
+    /// returns the number of voids filled, those without valid samples around stay voids
+    ///
+    /// the fill values are all computed from the original grid before any is written,
+    /// so filled voids don't feed into each other, and the result doesn't depend on the order
+    pub fn fill_voids(&mut self, radius: usize) -> usize {
+        let fills: Vec<(usize, i16)> = self
+            .void_indices()
+            .filter_map(|i| Some((i, self.fill_value(i, radius)?)))
+            .collect();
+        self.write_fills(fills)
+    }
+    /// same as [`Tile::fill_voids()`], but computes the fill values in parallel, with the same result
+    #[cfg(feature = "rayon")]
+    pub fn par_fill_voids(&mut self, radius: usize) -> usize {
+        let voids: Vec<usize> = self.void_indices().collect();
+        let fills: Vec<(usize, i16)> = voids
+            .into_par_iter()
+            .filter_map(|i| Some((i, self.fill_value(i, radius)?)))
+            .collect();
+        self.write_fills(fills)
+    }
+    /// same as [`Tile::par_fill_voids()`], but runs on `pool` instead of the global rayon thread pool
+    #[cfg(feature = "rayon")]
+    pub fn par_fill_voids_in(&mut self, radius: usize, pool: &rayon::ThreadPool) -> usize {
+        pool.install(|| self.par_fill_voids(radius))
+    }
+}
+
+// impl for non-pub fn-s
+impl Tile {
+    /// the indices of all voids in `data`
+    fn void_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, elev)| self.treats_as_void(**elev))
+            .map(|(i, _)| i)
+    }
+    /// the rounded mean of the valid samples within `radius` rows and columns of `data[i]`
+    fn fill_value(&self, i: usize, radius: usize) -> Option<i16> {
+        let extent = self.resolution.extent();
+        let (row, col) = (i / extent, i % extent);
+        let rows = row.saturating_sub(radius)..=(row + radius).min(extent - 1);
+        let (mut sum, mut count) = (0i64, 0i64);
+        for r in rows {
+            let cols = col.saturating_sub(radius)..=(col + radius).min(extent - 1);
+            for elev in &self.data[r * extent + cols.start()..=r * extent + cols.end()] {
+                if !self.treats_as_void(*elev) {
+                    sum += *elev as i64;
+                    count += 1;
+                }
+            }
+        }
+        (count > 0).then(|| round_valid(sum as f64 / count as f64, self.zero_is_void()))
+    }
+    /// write the `(index, elevation)` pairs of `fills` into `data`, returns how many there were
+    fn write_fills(&mut self, fills: Vec<(usize, i16)>) -> usize {
+        let filled = fills.len();
+        for (i, elev) in fills {
+            self.data[i] = elev;
+        }
+        filled
+    }
+}